@@ -1,39 +1,265 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    collections::{HashSet, VecDeque},
+    fs::write,
+    io::stdin,
+    path::PathBuf,
+};
 
 use super::{RunCommand, RunResult};
 use crate::{
-    command::{send_batched_tx, CommandContext},
-    policy::PolicyVersion,
-    CommandComplete, LogPolicy, SolanaAccount,
+    command::{
+        complete_unsigned,
+        sync::{input_hash, BatchSender, Checkpoint, Phase},
+        CommandContext,
+    },
+    format_identities,
+    policy::fetch_policy_with_identities,
+    CommandComplete, CommandOutput, InputFormat, LogPlan, LogPolicy, OutputFormat, SolanaAccount,
 };
-use borsh::BorshDeserialize;
+use anyhow::bail;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
 
+use solana_client::{client_error::ClientError, nonblocking::rpc_client::RpcClient};
 use solana_pubkey::Pubkey;
-use solana_signer::Signer;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
-use spl_token_2022::{
-    extension::{BaseStateWithExtensions, PodStateWithExtensions},
-    pod::PodMint,
-};
-use spl_token_metadata_interface::state::TokenMetadata;
 
+use yellowstone_shield_client::{accounts::Policy, instructions::ReplaceIdentityByKeyBuilder};
 use yellowstone_shield_client::{
-    accounts::{Policy, PolicyV2},
-    instructions::ReplaceIdentityBuilder,
-    types::Kind,
-};
-use yellowstone_shield_client::{
-    instructions::{AddIdentityBuilder, RemoveIdentityBuilder},
-    PolicyTrait,
+    instructions::{AddIdentityBuilder, RemoveIdentityByKeyBuilder},
+    DecodedPolicy,
 };
 
 const CHUNK_SIZE: usize = 20;
 
+/// Changes an identities command makes to a policy, computed before any transaction is sent.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityPlan {
+    #[serde_as(as = "DisplayFromStr")]
+    pub policy: Pubkey,
+    /// Identities cleared from the policy.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub remove: Vec<Pubkey>,
+    /// Identities overwritten with a new identity, as `(old, new)` pairs.
+    ///
+    /// An old identity equal to the default public key fills an empty slot.
+    #[serde_as(as = "Vec<(DisplayFromStr, DisplayFromStr)>")]
+    pub replace: Vec<(Pubkey, Pubkey)>,
+    /// Identities appended to the end of the policy.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub add: Vec<Pubkey>,
+    /// Increase of the rent-exempt balance of the policy account, in lamports.
+    pub rent_delta: u64,
+    /// Revision of the policy the plan was computed from, `None` for policies created before
+    /// revisions were recorded.
+    #[serde(default)]
+    pub revision: Option<u64>,
+}
+
+impl IdentityPlan {
+    async fn new(
+        client: &RpcClient,
+        policy: Pubkey,
+        account_data: &[u8],
+        remove: Vec<Pubkey>,
+        replace: Vec<(Pubkey, Pubkey)>,
+        add: Vec<Pubkey>,
+    ) -> Result<Self, ClientError> {
+        let data_len = account_data.len();
+        let revision = DecodedPolicy::try_from_bytes(account_data)
+            .ok()
+            .and_then(|policy| policy.revision);
+
+        // Only appended identities grow the account, removed identities leave an empty slot.
+        let rent_delta = if add.is_empty() {
+            0
+        } else {
+            let current = client
+                .get_minimum_balance_for_rent_exemption(data_len)
+                .await?;
+            let planned = client
+                .get_minimum_balance_for_rent_exemption(
+                    data_len + add.len() * std::mem::size_of::<Pubkey>(),
+                )
+                .await?;
+            planned.saturating_sub(current)
+        };
+
+        Ok(Self {
+            policy,
+            remove,
+            replace,
+            add,
+            rent_delta,
+            revision,
+        })
+    }
+
+    /// Number of transactions needed to apply the plan.
+    pub fn transactions(&self) -> usize {
+        [self.remove.len(), self.replace.len(), self.add.len()]
+            .iter()
+            .map(|len| len.div_ceil(CHUNK_SIZE))
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remove.is_empty() && self.replace.is_empty() && self.add.is_empty()
+    }
+
+    /// The part of the plan the `current` identities of the policy are still missing, sent
+    /// expecting `revision`.
+    ///
+    /// Every instruction addresses identities by key, so an identity removed, replaced or
+    /// added by a batch that landed without being recorded is recognized on chain.
+    pub fn remaining(&self, current: &[Pubkey], revision: Option<u64>) -> Self {
+        let current: HashSet<&Pubkey> = current.iter().collect();
+
+        let add: Vec<Pubkey> = self
+            .add
+            .iter()
+            .filter(|identity| !current.contains(identity))
+            .copied()
+            .collect();
+        // Rent grows linearly with the account size, each appended identity costs the same.
+        let rent_delta = if self.add.is_empty() {
+            0
+        } else {
+            self.rent_delta / self.add.len() as u64 * add.len() as u64
+        };
+
+        Self {
+            policy: self.policy,
+            remove: self
+                .remove
+                .iter()
+                .filter(|identity| current.contains(identity))
+                .copied()
+                .collect(),
+            replace: self
+                .replace
+                .iter()
+                .filter(|(_, identity)| !current.contains(identity))
+                .copied()
+                .collect(),
+            add,
+            rent_delta,
+            revision,
+        }
+    }
+}
+
+/// Prints the plan on dry runs, otherwise asks for confirmation unless `yes` is set.
+///
+/// Returns whether the plan should be sent.
+fn review_plan(
+    plan: &IdentityPlan,
+    dry_run: bool,
+    yes: bool,
+    output: OutputFormat,
+) -> anyhow::Result<bool> {
+    if dry_run {
+        LogPlan::new(plan).print(output);
+        return Ok(false);
+    }
+
+    if yes || plan.is_empty() {
+        return Ok(true);
+    }
+
+    // Prompt on stderr so stdout only carries the command result.
+    eprint!("{}", LogPlan::new(plan));
+    eprint!("Send {} transaction(s)? [y/N] ", plan.transactions());
+
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(true),
+        _ => bail!("Aborted, no transaction was sent"),
+    }
+}
+
+/// Computes the minimal changes turning the `current` identities buffer into `identities`,
+/// as `(remove, replace, add)`.
+///
+/// Identities in both are kept in place. Slots holding an identity that is not wanted,
+/// or no identity at all, are overwritten with the new identities first, the buffer only
+/// shrinks by removing from its tail and only grows by appending what is left.
+fn plan_update(
+    current: &[Pubkey],
+    identities: &[Pubkey],
+) -> (Vec<Pubkey>, Vec<(Pubkey, Pubkey)>, Vec<Pubkey>) {
+    let current_set: HashSet<_> = current.iter().collect();
+
+    let mut iden_to_replace_or_add = VecDeque::new();
+    let identities_set: HashSet<_> = identities.iter().collect();
+
+    for i in identities {
+        if !current_set.contains(&i) {
+            iden_to_replace_or_add.push_back(*i);
+        }
+    }
+
+    let mut iden_to_be_replaced_or_deleted_indices = current
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, p)| {
+            if p == &Pubkey::default() {
+                return Some((idx, true));
+            }
+            if !identities_set.contains(p) {
+                return Some((idx, false));
+            }
+            None
+        })
+        .collect::<VecDeque<(usize, bool)>>();
+
+    let len_current = current.len();
+    let len_identities = identities.len();
+
+    // REMOVE if current > identities
+    let len_diff = len_current.saturating_sub(len_identities);
+
+    let mut remove = Vec::new();
+    for _ in 0..len_diff {
+        if let Some((idx, already_deleted)) = iden_to_be_replaced_or_deleted_indices.pop_back() {
+            if !already_deleted {
+                remove.push(current[idx]);
+            }
+        }
+    }
+
+    let mut replace = Vec::new();
+
+    let min_len = usize::min(
+        iden_to_be_replaced_or_deleted_indices.len(),
+        iden_to_replace_or_add.len(),
+    );
+
+    for i in 0..min_len {
+        let (idx, _) = iden_to_be_replaced_or_deleted_indices[i];
+        let identity = iden_to_replace_or_add[i];
+        replace.push((current[idx], identity));
+    }
+
+    iden_to_be_replaced_or_deleted_indices.drain(0..min_len);
+    iden_to_replace_or_add.drain(0..min_len);
+
+    let add: Vec<_> = iden_to_replace_or_add.into_iter().collect();
+
+    (remove, replace, add)
+}
+
 /// Builder for adding a identities to a policy
 #[derive(Debug, Clone)]
 pub struct AddBatchCommandBuilder<'a> {
     mint: Option<&'a Pubkey>,
     identities: Option<Vec<Pubkey>>,
+    dry_run: bool,
+    yes: bool,
 }
 
 impl Default for AddBatchCommandBuilder<'_> {
@@ -48,6 +274,8 @@ impl<'a> AddBatchCommandBuilder<'a> {
         Self {
             mint: None,
             identities: None,
+            dry_run: false,
+            yes: false,
         }
     }
 
@@ -62,13 +290,30 @@ impl<'a> AddBatchCommandBuilder<'a> {
         self.identities = Some(identities);
         self
     }
+
+    /// Print the plan without sending any transaction
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Send the transactions without asking for confirmation
+    pub fn yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
 }
 
 #[async_trait::async_trait]
 impl RunCommand for AddBatchCommandBuilder<'_> {
     /// Execute the addition of a identity to the policy
     async fn run(&mut self, context: CommandContext) -> RunResult {
-        let CommandContext { keypair, client } = context;
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
 
         let mint = self.mint.expect("mint must be set");
 
@@ -77,9 +322,10 @@ impl RunCommand for AddBatchCommandBuilder<'_> {
         let mut identities = self.identities.take().expect("identities must be set");
         let mut seen = std::collections::HashSet::new();
         identities.retain(|pk| seen.insert(*pk));
+        let input = input_hash("add", &identities);
 
         let token_account = get_associated_token_address_with_program_id(
-            &keypair.pubkey(),
+            &authority.pubkey(),
             mint,
             &spl_token_2022::ID,
         );
@@ -87,96 +333,111 @@ impl RunCommand for AddBatchCommandBuilder<'_> {
         let account_data = client.get_account(&address).await?;
         let account_data: &[u8] = &account_data.data;
 
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
-
-        let current = match policy_version {
-            Kind::Policy => Policy::try_deserialize_identities(account_data),
-            Kind::PolicyV2 => PolicyV2::try_deserialize_identities(account_data),
-        }?;
+        let current = DecodedPolicy::try_from_bytes(account_data)?.identities;
 
-        let empty_identity_indices = current
+        let empty_slots = current
             .iter()
-            .enumerate()
-            .filter_map(|(idx, p)| {
-                if p == &Pubkey::default() {
-                    return Some(idx);
-                }
-                None
-            })
-            .collect::<Vec<usize>>();
+            .filter(|identity| **identity == Pubkey::default())
+            .count();
 
         let mut add_or_replace: Vec<Pubkey> = identities
             .into_iter()
             .filter(|identity| !current.contains(identity))
             .collect();
 
+        // Fill the empty slots first, each replace takes the first empty slot on chain.
         let mut replace = Vec::new();
 
-        for i in empty_identity_indices {
+        for _ in 0..empty_slots {
             if let Some(iden) = add_or_replace.pop() {
-                replace.push((i, iden));
+                replace.push((Pubkey::default(), iden));
             }
         }
 
-        // REPLACE
-        send_batched_tx(
+        let plan = IdentityPlan::new(
             &client,
-            &keypair,
-            &replace,
-            CHUNK_SIZE,
-            |(idx, identity)| {
-                ReplaceIdentityBuilder::new()
-                    .policy(address)
-                    .mint(*mint)
-                    .token_account(token_account)
-                    .owner(keypair.pubkey())
-                    .identity(*identity)
-                    .index(*idx as u64)
-                    .instruction()
-            },
+            address,
+            account_data,
+            Vec::new(),
+            replace,
+            add_or_replace,
         )
         .await?;
 
-        // ADD
-        send_batched_tx(&client, &keypair, &add_or_replace, CHUNK_SIZE, |identity| {
-            AddIdentityBuilder::new()
-                .policy(address)
-                .mint(*mint)
-                .token_account(token_account)
-                .payer(keypair.pubkey())
-                .owner(keypair.pubkey())
-                .identity(*identity)
-                .instruction()
-        })
-        .await?;
+        let checkpoint = if authority.is_offline() {
+            Checkpoint::disabled(plan)
+        } else {
+            Checkpoint::open(&address, &input, plan, &current)?
+        };
+        let plan = checkpoint.plan.clone();
+
+        if !review_plan(
+            &plan,
+            self.dry_run,
+            self.yes || authority.is_offline(),
+            output,
+        )? {
+            return Ok(CommandComplete::Plan(plan));
+        }
 
-        let account_data = client.get_account(&address).await?;
-        let account_data: &[u8] = &account_data.data;
+        let mut sender =
+            BatchSender::new(&client, &authority, &send_config, checkpoint, CHUNK_SIZE);
 
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
+        // REPLACE
+        sender
+            .send(
+                Phase::Replace,
+                &plan.replace,
+                |(old, identity), expected_revision| {
+                    let mut builder = ReplaceIdentityByKeyBuilder::new();
+                    builder
+                        .policy(address)
+                        .mint(*mint)
+                        .token_account(token_account)
+                        .owner(authority.pubkey())
+                        .old_identity(*old)
+                        .new_identity(*identity);
+                    if let Some(revision) = expected_revision {
+                        builder.expected_revision(revision);
+                    }
+                    builder.instruction()
+                },
+            )
+            .await?;
 
-        let policy = match policy_version {
-            Kind::Policy => PolicyVersion::V1(Policy::from_bytes(&account_data[..Policy::LEN])?),
-            Kind::PolicyV2 => {
-                PolicyVersion::V2(PolicyV2::from_bytes(&account_data[..PolicyV2::LEN])?)
-            }
-        };
+        // ADD
+        sender
+            .send(Phase::Add, &plan.add, |identity, expected_revision| {
+                let mut builder = AddIdentityBuilder::new();
+                builder
+                    .policy(address)
+                    .mint(*mint)
+                    .token_account(token_account)
+                    .payer(authority.pubkey())
+                    .owner(authority.pubkey())
+                    .identity(*identity);
+                if let Some(revision) = expected_revision {
+                    builder.expected_revision(revision);
+                }
+                builder.instruction()
+            })
+            .await?;
 
-        let mint_data = client.get_account(mint).await?;
-        let mint_account_data: &[u8] = &mint_data.data;
+        let transactions = sender.finish()?;
 
-        let mint_pod = PodStateWithExtensions::<PodMint>::unpack(mint_account_data).unwrap();
-        let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>().unwrap();
-        let token_metadata = TokenMetadata::try_from_slice(mint_bytes).unwrap();
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
 
-        let identities = match policy_version {
-            Kind::Policy => Policy::try_deserialize_identities(account_data)?,
-            Kind::PolicyV2 => PolicyV2::try_deserialize_identities(account_data)?,
-        };
+        let (policy, identities, token_metadata) =
+            fetch_policy_with_identities(&client, mint).await?;
 
-        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities)).log();
+        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities))
+            .signatures(&transactions.signatures)
+            .batches(&transactions.batches)
+            .print(output);
 
-        Ok(CommandComplete(
+        Ok(CommandComplete::Policy(
             SolanaAccount(*mint, Some(token_metadata)),
             SolanaAccount(address, Some(policy)),
         ))
@@ -187,6 +448,8 @@ impl RunCommand for AddBatchCommandBuilder<'_> {
 pub struct UpdateBatchCommandBuilder<'a> {
     mint: Option<&'a Pubkey>,
     identities: Option<Vec<Pubkey>>,
+    dry_run: bool,
+    yes: bool,
 }
 
 impl Default for UpdateBatchCommandBuilder<'_> {
@@ -201,6 +464,8 @@ impl<'a> UpdateBatchCommandBuilder<'a> {
         Self {
             mint: None,
             identities: None,
+            dry_run: false,
+            yes: false,
         }
     }
 
@@ -215,13 +480,30 @@ impl<'a> UpdateBatchCommandBuilder<'a> {
         self.identities = Some(identities);
         self
     }
+
+    /// Print the plan without sending any transaction
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Send the transactions without asking for confirmation
+    pub fn yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
 }
 
 #[async_trait::async_trait]
 impl RunCommand for UpdateBatchCommandBuilder<'_> {
     /// Execute replace/update of identities
     async fn run(&mut self, context: CommandContext) -> RunResult {
-        let CommandContext { keypair, client } = context;
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
 
         let mint = self.mint.expect("mint must be set");
 
@@ -231,9 +513,10 @@ impl RunCommand for UpdateBatchCommandBuilder<'_> {
         let mut identities = self.identities.take().expect("identities must be set");
         let mut seen = std::collections::HashSet::new();
         identities.retain(|pk| seen.insert(*pk));
+        let input = input_hash("update", &identities);
 
         let token_account = get_associated_token_address_with_program_id(
-            &keypair.pubkey(),
+            &authority.pubkey(),
             mint,
             &spl_token_2022::ID,
         );
@@ -241,143 +524,107 @@ impl RunCommand for UpdateBatchCommandBuilder<'_> {
         let account_data = client.get_account(&address).await?;
         let account_data: &[u8] = &account_data.data;
 
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
-
-        let current = match policy_version {
-            Kind::Policy => Policy::try_deserialize_identities(account_data)?,
-            Kind::PolicyV2 => PolicyV2::try_deserialize_identities(account_data)?,
-        };
-
-        let current_set: HashSet<_> = current.iter().collect();
-
-        let mut iden_to_replace_or_add = VecDeque::new();
-        let identities_set: HashSet<_> = identities.iter().collect();
-
-        for i in &identities {
-            if !current_set.contains(&i) {
-                iden_to_replace_or_add.push_back(*i);
-            }
-        }
-
-        let mut iden_to_be_replaced_or_deleted_indices = current
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, p)| {
-                if p == &Pubkey::default() {
-                    return Some((idx, true));
-                }
-                if !identities_set.contains(p) {
-                    return Some((idx, false));
-                }
-                None
-            })
-            .collect::<VecDeque<(usize, bool)>>();
-
-        let len_current = current.len();
-        let len_identities = identities.len();
-
-        // REMOVE if current > identities
-        let len_diff = len_current.saturating_sub(len_identities);
+        let current = DecodedPolicy::try_from_bytes(account_data)?.identities;
 
-        let mut remove = Vec::new();
-        for _ in 0..len_diff {
-            if let Some((idx, already_deleted)) = iden_to_be_replaced_or_deleted_indices.pop_back()
-            {
-                if !already_deleted {
-                    remove.push(idx);
-                }
-            }
-        }
+        let (remove, replace, add) = plan_update(&current, &identities);
 
-        let mut replace = Vec::new();
+        let plan = IdentityPlan::new(&client, address, account_data, remove, replace, add).await?;
 
-        let min_len = usize::min(
-            iden_to_be_replaced_or_deleted_indices.len(),
-            iden_to_replace_or_add.len(),
-        );
-
-        for i in 0..min_len {
-            let (idx, _) = iden_to_be_replaced_or_deleted_indices[i];
-            let identity = iden_to_replace_or_add[i];
-            replace.push((idx, identity));
+        let checkpoint = if authority.is_offline() {
+            Checkpoint::disabled(plan)
+        } else {
+            Checkpoint::open(&address, &input, plan, &current)?
+        };
+        let plan = checkpoint.plan.clone();
+
+        if !review_plan(
+            &plan,
+            self.dry_run,
+            self.yes || authority.is_offline(),
+            output,
+        )? {
+            return Ok(CommandComplete::Plan(plan));
         }
 
-        iden_to_be_replaced_or_deleted_indices.drain(0..min_len);
-        iden_to_replace_or_add.drain(0..min_len);
-
-        let add: Vec<_> = iden_to_replace_or_add.into_iter().collect();
+        let mut sender =
+            BatchSender::new(&client, &authority, &send_config, checkpoint, CHUNK_SIZE);
 
         // REMOVE
-        send_batched_tx(&client, &keypair, &remove, CHUNK_SIZE, |idx| {
-            RemoveIdentityBuilder::new()
-                .policy(address)
-                .mint(*mint)
-                .token_account(token_account)
-                .owner(keypair.pubkey())
-                .index(*idx as u64)
-                .instruction()
-        })
-        .await?;
+        sender
+            .send(
+                Phase::Remove,
+                &plan.remove,
+                |identity, expected_revision| {
+                    let mut builder = RemoveIdentityByKeyBuilder::new();
+                    builder
+                        .policy(address)
+                        .mint(*mint)
+                        .token_account(token_account)
+                        .owner(authority.pubkey())
+                        .identity(*identity);
+                    if let Some(revision) = expected_revision {
+                        builder.expected_revision(revision);
+                    }
+                    builder.instruction()
+                },
+            )
+            .await?;
 
         // REPLACE
-        send_batched_tx(
-            &client,
-            &keypair,
-            &replace,
-            CHUNK_SIZE,
-            |(idx, identity)| {
-                ReplaceIdentityBuilder::new()
+        sender
+            .send(
+                Phase::Replace,
+                &plan.replace,
+                |(old, identity), expected_revision| {
+                    let mut builder = ReplaceIdentityByKeyBuilder::new();
+                    builder
+                        .policy(address)
+                        .mint(*mint)
+                        .token_account(token_account)
+                        .owner(authority.pubkey())
+                        .old_identity(*old)
+                        .new_identity(*identity);
+                    if let Some(revision) = expected_revision {
+                        builder.expected_revision(revision);
+                    }
+                    builder.instruction()
+                },
+            )
+            .await?;
+
+        // ADD
+        sender
+            .send(Phase::Add, &plan.add, |identity, expected_revision| {
+                let mut builder = AddIdentityBuilder::new();
+                builder
                     .policy(address)
                     .mint(*mint)
                     .token_account(token_account)
-                    .owner(keypair.pubkey())
-                    .identity(*identity)
-                    .index(*idx as u64)
-                    .instruction()
-            },
-        )
-        .await?;
-
-        // ADD
-        send_batched_tx(&client, &keypair, &add, CHUNK_SIZE, |identity| {
-            AddIdentityBuilder::new()
-                .policy(address)
-                .mint(*mint)
-                .token_account(token_account)
-                .payer(keypair.pubkey())
-                .owner(keypair.pubkey())
-                .identity(*identity)
-                .instruction()
-        })
-        .await?;
-
-        let account_data = client.get_account(&address).await?;
-        let account_data: &[u8] = &account_data.data;
-
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
-
-        let policy = match policy_version {
-            Kind::Policy => PolicyVersion::V1(Policy::from_bytes(&account_data[..Policy::LEN])?),
-            Kind::PolicyV2 => {
-                PolicyVersion::V2(PolicyV2::from_bytes(&account_data[..PolicyV2::LEN])?)
-            }
-        };
+                    .payer(authority.pubkey())
+                    .owner(authority.pubkey())
+                    .identity(*identity);
+                if let Some(revision) = expected_revision {
+                    builder.expected_revision(revision);
+                }
+                builder.instruction()
+            })
+            .await?;
 
-        let mint_data = client.get_account(mint).await?;
-        let mint_account_data: &[u8] = &mint_data.data;
+        let transactions = sender.finish()?;
 
-        let mint_pod = PodStateWithExtensions::<PodMint>::unpack(mint_account_data).unwrap();
-        let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>().unwrap();
-        let token_metadata = TokenMetadata::try_from_slice(mint_bytes).unwrap();
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
 
-        let identities = match policy_version {
-            Kind::Policy => Policy::try_deserialize_identities(account_data)?,
-            Kind::PolicyV2 => PolicyV2::try_deserialize_identities(account_data)?,
-        };
+        let (policy, identities, token_metadata) =
+            fetch_policy_with_identities(&client, mint).await?;
 
-        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities)).log();
+        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities))
+            .signatures(&transactions.signatures)
+            .batches(&transactions.batches)
+            .print(output);
 
-        Ok(CommandComplete(
+        Ok(CommandComplete::Policy(
             SolanaAccount(*mint, Some(token_metadata)),
             SolanaAccount(address, Some(policy)),
         ))
@@ -388,6 +635,8 @@ impl RunCommand for UpdateBatchCommandBuilder<'_> {
 pub struct RemoveBatchCommandBuilder<'a> {
     mint: Option<&'a Pubkey>,
     identities: Option<Vec<Pubkey>>,
+    dry_run: bool,
+    yes: bool,
 }
 
 impl Default for RemoveBatchCommandBuilder<'_> {
@@ -402,6 +651,8 @@ impl<'a> RemoveBatchCommandBuilder<'a> {
         Self {
             mint: None,
             identities: None,
+            dry_run: false,
+            yes: false,
         }
     }
 
@@ -416,13 +667,30 @@ impl<'a> RemoveBatchCommandBuilder<'a> {
         self.identities = Some(identities);
         self
     }
+
+    /// Print the plan without sending any transaction
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Send the transactions without asking for confirmation
+    pub fn yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
 }
 
 #[async_trait::async_trait]
 impl RunCommand for RemoveBatchCommandBuilder<'_> {
     /// Execute the removal of an identity from the policy
     async fn run(&mut self, context: CommandContext) -> RunResult {
-        let CommandContext { keypair, client } = context;
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
 
         let mint = self.mint.expect("mint must be set");
         // PDA seeds are same for both Policy and PolicyV2
@@ -431,9 +699,10 @@ impl RunCommand for RemoveBatchCommandBuilder<'_> {
         let mut identities = self.identities.take().expect("identities must be set");
         let mut seen = std::collections::HashSet::new();
         identities.retain(|pk| seen.insert(*pk));
+        let input = input_hash("remove", &identities);
 
         let token_account = get_associated_token_address_with_program_id(
-            &keypair.pubkey(),
+            &authority.pubkey(),
             mint,
             &spl_token_2022::ID,
         );
@@ -441,62 +710,265 @@ impl RunCommand for RemoveBatchCommandBuilder<'_> {
         let account_data = client.get_account(&address).await?;
         let account_data: &[u8] = &account_data.data;
 
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
+        let current = DecodedPolicy::try_from_bytes(account_data)?.identities;
 
-        let current = match policy_version {
-            Kind::Policy => Policy::try_deserialize_identities(account_data),
-            Kind::PolicyV2 => PolicyV2::try_deserialize_identities(account_data),
-        }?;
-
-        let remove: Vec<usize> = identities
+        let remove: Vec<Pubkey> = identities
             .into_iter()
-            .filter_map(|identity| {
-                current
-                    .iter()
-                    .position(|&current_identity| current_identity == identity)
-            })
+            .filter(|identity| current.contains(identity))
             .collect();
 
-        send_batched_tx(&client, &keypair, &remove, CHUNK_SIZE, |idx| {
-            RemoveIdentityBuilder::new()
-                .policy(address)
-                .mint(*mint)
-                .token_account(token_account)
-                .owner(keypair.pubkey())
-                .index(*idx as u64)
-                .instruction()
-        })
+        let plan = IdentityPlan::new(
+            &client,
+            address,
+            account_data,
+            remove,
+            Vec::new(),
+            Vec::new(),
+        )
         .await?;
 
-        let account_data = client.get_account(&address).await?;
-        let account_data: &[u8] = &account_data.data;
+        let checkpoint = if authority.is_offline() {
+            Checkpoint::disabled(plan)
+        } else {
+            Checkpoint::open(&address, &input, plan, &current)?
+        };
+        let plan = checkpoint.plan.clone();
+
+        if !review_plan(
+            &plan,
+            self.dry_run,
+            self.yes || authority.is_offline(),
+            output,
+        )? {
+            return Ok(CommandComplete::Plan(plan));
+        }
+
+        let mut sender =
+            BatchSender::new(&client, &authority, &send_config, checkpoint, CHUNK_SIZE);
+
+        sender
+            .send(
+                Phase::Remove,
+                &plan.remove,
+                |identity, expected_revision| {
+                    let mut builder = RemoveIdentityByKeyBuilder::new();
+                    builder
+                        .policy(address)
+                        .mint(*mint)
+                        .token_account(token_account)
+                        .owner(authority.pubkey())
+                        .identity(*identity);
+                    if let Some(revision) = expected_revision {
+                        builder.expected_revision(revision);
+                    }
+                    builder.instruction()
+                },
+            )
+            .await?;
+
+        let transactions = sender.finish()?;
+
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
+
+        let (policy, identities, token_metadata) =
+            fetch_policy_with_identities(&client, mint).await?;
+
+        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities))
+            .signatures(&transactions.signatures)
+            .batches(&transactions.batches)
+            .print(output);
+
+        Ok(CommandComplete::Policy(
+            SolanaAccount(*mint, Some(token_metadata)),
+            SolanaAccount(address, Some(policy)),
+        ))
+    }
+}
+
+/// Builder for exporting the identities of a policy
+pub struct ExportCommandBuilder<'a> {
+    mint: Option<&'a Pubkey>,
+    out: Option<PathBuf>,
+    format: InputFormat,
+}
+
+impl Default for ExportCommandBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ExportCommandBuilder<'a> {
+    /// Create a new ExportCommandBuilder
+    pub fn new() -> Self {
+        Self {
+            mint: None,
+            out: None,
+            format: InputFormat::Auto,
+        }
+    }
 
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
+    /// Set the mint address
+    pub fn mint(mut self, mint: &'a Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
 
-        let policy = match policy_version {
-            Kind::Policy => PolicyVersion::V1(Policy::from_bytes(&account_data[..Policy::LEN])?),
-            Kind::PolicyV2 => {
-                PolicyVersion::V2(PolicyV2::from_bytes(&account_data[..PolicyV2::LEN])?)
+    /// Set the file the identities are written to, stdout when unset
+    pub fn out(mut self, out: Option<PathBuf>) -> Self {
+        self.out = out;
+        self
+    }
+
+    /// Set the format of the exported identities
+    pub fn format(mut self, format: InputFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for ExportCommandBuilder<'_> {
+    /// Execute the export of the identities of the policy
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority: _,
+            client,
+            output: _,
+            send_config: _,
+        } = context;
+
+        let mint = self.mint.expect("mint must be set");
+        // PDA seeds are same for both Policy and PolicyV2
+        let (address, _) = Policy::find_pda(mint);
+
+        let account = client.get_account(&address).await?;
+        let policy = DecodedPolicy::try_from_bytes(&account.data)?;
+
+        // Empty slots left by removals are not identities and would not parse back.
+        let identities: Vec<Pubkey> = policy.active_identities().copied().collect();
+        let exported = format_identities(&identities, self.format, self.out.as_deref());
+
+        match &self.out {
+            Some(out) => {
+                write(out, exported)?;
+                info!(
+                    "Exported {} identities to {}",
+                    identities.len(),
+                    out.display()
+                );
             }
+            None => print!("{}", exported),
+        }
+
+        Ok(CommandComplete::Identities(identities))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(count: usize) -> Vec<Pubkey> {
+        (0..count).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn test_remaining_plan_skips_landed_batches() {
+        let [a, b, c, d, e] = keys(5)[..] else {
+            unreachable!()
+        };
+        let plan = IdentityPlan {
+            policy: Pubkey::new_unique(),
+            remove: vec![a],
+            replace: vec![(b, c), (Pubkey::default(), d)],
+            add: vec![e, Pubkey::new_unique()],
+            rent_delta: 2 * 222_720,
+            revision: Some(3),
         };
 
-        let mint_data = client.get_account(mint).await?;
-        let mint_account_data: &[u8] = &mint_data.data;
+        // The remove and the first replace were recorded, the add of `e` landed but its
+        // confirmation timed out before it was recorded.
+        let remaining = plan.remaining(&[Pubkey::default(), c, Pubkey::default(), e], Some(6));
+
+        assert!(remaining.remove.is_empty());
+        assert_eq!(remaining.replace, vec![(Pubkey::default(), d)]);
+        assert_eq!(remaining.add, vec![plan.add[1]]);
+        assert_eq!(remaining.rent_delta, 222_720);
+        assert_eq!(remaining.revision, Some(6));
+
+        let done = plan.remaining(&[c, d, e, plan.add[1]], Some(8));
+        assert!(done.is_empty());
+        assert_eq!(done.rent_delta, 0);
+    }
+
+    #[test]
+    fn test_plan_update_keeps_shared_identities() {
+        let current = keys(3);
+
+        let (remove, replace, add) = plan_update(&current, &[current[2], current[0], current[1]]);
 
-        let mint_pod = PodStateWithExtensions::<PodMint>::unpack(mint_account_data).unwrap();
-        let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>().unwrap();
-        let token_metadata = TokenMetadata::try_from_slice(mint_bytes).unwrap();
+        assert!(remove.is_empty());
+        assert!(replace.is_empty());
+        assert!(add.is_empty());
+    }
 
-        let identities = match policy_version {
-            Kind::Policy => Policy::try_deserialize_identities(account_data)?,
-            Kind::PolicyV2 => PolicyV2::try_deserialize_identities(account_data)?,
+    #[test]
+    fn test_plan_update_fills_holes() {
+        let [a, b, c, d] = keys(4)[..] else {
+            unreachable!()
         };
+        let current = vec![a, Pubkey::default(), b, Pubkey::default()];
 
-        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities)).log();
+        let (remove, replace, add) = plan_update(&current, &[a, b, c, d]);
 
-        Ok(CommandComplete(
-            SolanaAccount(*mint, Some(token_metadata)),
-            SolanaAccount(address, Some(policy)),
-        ))
+        assert!(remove.is_empty());
+        assert_eq!(
+            replace,
+            vec![(Pubkey::default(), c), (Pubkey::default(), d)]
+        );
+        assert!(add.is_empty());
+    }
+
+    #[test]
+    fn test_plan_update_replaces_then_appends() {
+        let [a, b, c, d, e] = keys(5)[..] else {
+            unreachable!()
+        };
+
+        let (remove, replace, add) = plan_update(&[a, b], &[a, c, d, e]);
+
+        assert!(remove.is_empty());
+        assert_eq!(replace, vec![(b, c)]);
+        assert_eq!(add, vec![d, e]);
+    }
+
+    #[test]
+    fn test_plan_update_removes_from_tail() {
+        let [a, b, c, d, e] = keys(5)[..] else {
+            unreachable!()
+        };
+
+        // Two slots must go: the unwanted identities at the end of the buffer are removed and
+        // the one left in the middle is overwritten.
+        let (remove, replace, add) = plan_update(&[b, a, c, d], &[a, e]);
+
+        assert_eq!(remove, vec![d, c]);
+        assert_eq!(replace, vec![(b, e)]);
+        assert!(add.is_empty());
+    }
+
+    #[test]
+    fn test_plan_update_skips_removing_empty_slots() {
+        let [a, b] = keys(2)[..] else { unreachable!() };
+
+        // The empty tail slot counts towards the shrink without a remove instruction.
+        let (remove, replace, add) = plan_update(&[a, b, Pubkey::default()], &[a]);
+
+        assert_eq!(remove, vec![b]);
+        assert!(replace.is_empty());
+        assert!(add.is_empty());
     }
 }
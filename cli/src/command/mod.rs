@@ -1,30 +1,140 @@
 pub mod identity;
 pub mod policy;
+pub mod submit;
+pub mod sync;
+
+use std::time::Duration;
 
 use anyhow::Result;
-use log::info;
+use log::{debug, info, warn};
 use solana_client::client_error::ClientError;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_commitment_config::CommitmentConfig;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
+use solana_signature::Signature;
 use solana_signer::Signer;
+use solana_transaction::Transaction;
 use spl_token_metadata_interface::state::TokenMetadata;
 use yellowstone_shield_client::TransactionBuilder;
 
-use crate::policy::PolicyVersion;
+use crate::{
+    identity::IdentityPlan,
+    policy::{PolicySummary, PolicyVersion},
+    sync::Phase,
+    CommandOutput, LogUnsignedTransactions, OutputFormat,
+};
 
 pub struct CommandContext {
     pub client: RpcClient,
-    pub keypair: Keypair,
+    /// Owner of the policy tokens, paying for and signing the transactions.
+    pub authority: Authority,
+    /// Format of the results the command prints to stdout.
+    pub output: OutputFormat,
+    pub send_config: SendConfig,
+}
+
+/// Default number of batches of a step sent concurrently.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 8;
+
+/// How the transactions of a command are sent.
+#[derive(Debug, Clone, Copy)]
+pub struct SendConfig {
+    /// Maximum number of batches of a step sent concurrently.
+    pub max_in_flight: usize,
+    /// Priority fee paid per compute unit, in micro-lamports.
+    pub compute_unit_price: Option<u64>,
+    /// Compute units requested by every transaction. Estimated by simulating each transaction
+    /// when unset and a priority fee is paid, so the fee is not paid for unused units.
+    pub compute_unit_limit: Option<u32>,
+}
+
+impl Default for SendConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            compute_unit_price: None,
+            compute_unit_limit: None,
+        }
+    }
+}
+
+/// Owner of the policy tokens of a command.
+pub enum Authority {
+    /// Signs and sends the transactions with a local keypair.
+    Keypair(Keypair),
+    /// Leaves the transactions unsigned so they can be signed externally, e.g. by a hardware
+    /// wallet or a multisig, and broadcast with `submit`.
+    Offline(Pubkey),
+}
+
+impl Authority {
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            Authority::Keypair(keypair) => keypair.pubkey(),
+            Authority::Offline(owner) => *owner,
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        matches!(self, Authority::Offline(_))
+    }
+}
+
+/// A transaction missing the signature of an offline authority.
+#[derive(Debug, Clone)]
+pub struct UnsignedTransaction {
+    pub transaction: Transaction,
+    /// The Shield and token instructions of the transaction, without compute budget
+    /// instructions, for signers that build their own transactions.
+    pub instructions: Vec<Instruction>,
+}
+
+/// A batch of an identities plan confirmed on chain.
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    pub phase: Phase,
+    /// Index of the batch within its phase.
+    pub batch: usize,
+    pub signature: Signature,
+}
+
+/// Transactions produced by a command.
+#[derive(Debug, Default)]
+pub struct Transactions {
+    /// Signatures of the transactions signed with the keypair and confirmed.
+    pub signatures: Vec<Signature>,
+    /// Transactions left for an offline authority to sign.
+    pub unsigned: Vec<UnsignedTransaction>,
+    /// Batches confirmed by identities commands, in order.
+    pub batches: Vec<BatchResult>,
+}
+
+impl Transactions {
+    pub fn extend(&mut self, other: Transactions) {
+        self.signatures.extend(other.signatures);
+        self.unsigned.extend(other.unsigned);
+        self.batches.extend(other.batches);
+    }
 }
 
 pub struct SolanaAccount<T>(pub Pubkey, pub Option<T>);
-pub struct CommandComplete(
-    pub SolanaAccount<TokenMetadata>,
-    pub SolanaAccount<PolicyVersion>,
-);
+
+pub enum CommandComplete {
+    /// A single policy and the mint it is bound to.
+    Policy(SolanaAccount<TokenMetadata>, SolanaAccount<PolicyVersion>),
+    /// A summary of several policies.
+    Policies(Vec<PolicySummary>),
+    /// The changes an identities command would make, returned by dry runs.
+    Plan(IdentityPlan),
+    /// Transactions to be signed by an offline authority.
+    Unsigned(Vec<UnsignedTransaction>),
+    /// Identities of a policy, returned by `identities export`.
+    Identities(Vec<Pubkey>),
+    /// Signatures of the transactions broadcast by `submit`.
+    Signatures(Vec<Signature>),
+}
 
 pub type RunResult = Result<CommandComplete>;
 
@@ -33,42 +143,150 @@ pub trait RunCommand {
     async fn run(&mut self, context: CommandContext) -> RunResult;
 }
 
-async fn send_batched_tx<T, F>(
+/// Number of blockhashes a transaction is signed with before giving up.
+const MAX_SEND_ATTEMPTS: usize = 3;
+
+/// Delay before resending a transaction that could not be confirmed.
+const RESEND_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Signs and sends a transaction with the authority and `signers`, or leaves it for the
+/// authority to sign when it is offline.
+async fn send_tx(
     client: &RpcClient,
-    keypair: &Keypair,
-    items: &[T],
-    chunk_size: usize,
-    mut instruction_builder: F,
-) -> Result<(), ClientError>
-where
-    T: Clone,
-    F: FnMut(&T) -> Instruction,
-{
-    for batch in items.chunks(chunk_size) {
-        let instructions: Vec<_> = batch.iter().map(&mut instruction_builder).collect();
-
-        if instructions.is_empty() {
-            continue;
+    authority: &Authority,
+    config: &SendConfig,
+    instructions: Vec<Instruction>,
+    signers: &[&Keypair],
+) -> Result<Transactions, ClientError> {
+    send_tx_with_spinner(client, authority, config, instructions, signers, true).await
+}
+
+async fn send_tx_with_spinner(
+    client: &RpcClient,
+    authority: &Authority,
+    config: &SendConfig,
+    instructions: Vec<Instruction>,
+    signers: &[&Keypair],
+    spinner: bool,
+) -> Result<Transactions, ClientError> {
+    let mut transactions = Transactions::default();
+    let payer = authority.pubkey();
+
+    let mut builder = TransactionBuilder::build();
+    builder.instructions(instructions.clone()).payer(&payer);
+    for signer in signers {
+        builder.signer(signer);
+    }
+
+    if let Some(micro_lamports) = config.compute_unit_price {
+        builder.compute_unit_price(micro_lamports);
+    }
+
+    match config.compute_unit_limit {
+        Some(units) => {
+            builder.compute_unit_limit(units);
+        }
+        None if config.compute_unit_price.is_some() => {
+            let units = builder.estimate_compute_unit_limit(client).await?;
+            debug!("Estimated compute unit limit: {}", units);
+            builder.compute_unit_limit(units);
+        }
+        None => {}
+    }
+
+    match authority {
+        Authority::Keypair(keypair) => {
+            builder.signer(keypair);
+            let signature = send_with_retries(client, &mut builder, spinner).await?;
+
+            info!("Transaction signature: {}", signature);
+            transactions.signatures.push(signature);
+        }
+        Authority::Offline(_) => {
+            let last_blockhash = client.get_latest_blockhash().await?;
+            builder.recent_blockhash(last_blockhash);
+
+            transactions.unsigned.push(UnsignedTransaction {
+                transaction: builder.partial_transaction(),
+                instructions,
+            });
         }
+    }
+
+    Ok(transactions)
+}
 
+/// Signs the transaction with a fresh blockhash and sends it, retrying when it could not be
+/// confirmed.
+///
+/// The signed transaction is resent until its blockhash expires, and only then signed again
+/// with a fresh blockhash, so a transaction that lands late is never sent twice. Transactions
+/// rejected by the program are not retried.
+async fn send_with_retries(
+    client: &RpcClient,
+    builder: &mut TransactionBuilder<'_>,
+    spinner: bool,
+) -> Result<Signature, ClientError> {
+    let mut attempt = 1;
+
+    loop {
         let last_blockhash = client.get_latest_blockhash().await?;
+        let tx = builder.recent_blockhash(last_blockhash).transaction();
+        let signature = tx.signatures[0];
+
+        let error = loop {
+            let result = if spinner {
+                client
+                    .send_and_confirm_transaction_with_spinner_and_commitment(
+                        &tx,
+                        CommitmentConfig::confirmed(),
+                    )
+                    .await
+            } else {
+                client.send_and_confirm_transaction(&tx).await
+            };
 
-        let tx = TransactionBuilder::build()
-            .instructions(instructions)
-            .signer(keypair)
-            .payer(&keypair.pubkey())
-            .recent_blockhash(last_blockhash)
-            .transaction();
-
-        let signature = client
-            .send_and_confirm_transaction_with_spinner_and_commitment(
-                &tx,
-                CommitmentConfig::confirmed(),
-            )
-            .await?;
-
-        info!("Transaction signature: {}", signature);
+            let e = match result {
+                Ok(signature) => return Ok(signature),
+                Err(e) if e.get_transaction_error().is_some() => return Err(e),
+                Err(e) => e,
+            };
+
+            // Checked before the status so a transaction landing in between is still seen.
+            let expired = !client
+                .is_blockhash_valid(&last_blockhash, CommitmentConfig::processed())
+                .await?;
+
+            // Confirming can fail after the transaction landed, e.g. on an RPC timeout.
+            match client.get_signature_status(&signature).await? {
+                Some(Ok(())) => return Ok(signature),
+                Some(Err(tx_error)) => return Err(tx_error.into()),
+                None if expired => break e,
+                None => {
+                    warn!(
+                        "Transaction {} not confirmed: {}, resending until its blockhash expires",
+                        signature, e
+                    );
+                    tokio::time::sleep(RESEND_INTERVAL).await;
+                }
+            }
+        };
+
+        if attempt == MAX_SEND_ATTEMPTS {
+            return Err(error);
+        }
+
+        warn!(
+            "Transaction expired (attempt {}/{}): {}, retrying with a fresh blockhash",
+            attempt, MAX_SEND_ATTEMPTS, error
+        );
+        attempt += 1;
     }
+}
+
+/// Prints the transactions left for an offline authority as the result of a command.
+fn complete_unsigned(unsigned: Vec<UnsignedTransaction>, output: OutputFormat) -> RunResult {
+    LogUnsignedTransactions::new(&unsigned).print(output);
 
-    Ok(())
+    Ok(CommandComplete::Unsigned(unsigned))
 }
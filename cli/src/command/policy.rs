@@ -1,33 +1,48 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::bail;
 use borsh::BorshDeserialize;
-use log::info;
-use solana_commitment_config::CommitmentConfig;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcProgramAccountsConfig};
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
 use solana_signer::Signer;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_pod::optional_keys::OptionalNonZeroPubkey;
 use spl_token_2022::{
-    extension::{BaseStateWithExtensions, ExtensionType, PodStateWithExtensions},
+    extension::{
+        BaseStateWithExtensions, ExtensionType, PodStateWithExtensions, StateWithExtensions,
+    },
     pod::PodMint,
-    state::Mint,
+    state::{Account as TokenAccount, Mint},
 };
-use spl_token_metadata_interface::state::TokenMetadata;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
 use yellowstone_shield_client::{
-    accounts::{Policy, PolicyV2},
-    instructions::{ClosePolicyBuilder, CreatePolicyBuilder},
+    accounts::{Policy, PolicyV2, PolicyV3},
+    instructions::{
+        ClosePolicyBuilder, CreatePolicyBuilder, LockPolicyBuilder, MigratePolicyBuilder,
+        SetMinBalanceBuilder,
+    },
     types::{Kind, PermissionStrategy},
-    CreateAccountBuilder, CreateAsscoiatedTokenAccountBuilder, InitializeMetadataBuilder,
-    InitializeMint2Builder, MetadataPointerInitializeBuilder, PolicyTrait,
-    TokenExtensionsMintToBuilder, TransactionBuilder,
+    CreateAccountBuilder, CreateAsscoiatedTokenAccountBuilder, DecodedPolicy,
+    InitializeMetadataBuilder, InitializeMint2Builder, MetadataPointerInitializeBuilder,
+    RentTopUpBuilder, RevokeMintAuthorityBuilder, TokenExtensionsMintToBuilder,
+    TokenExtensionsTransferBuilder, UpdateMetadataFieldBuilder,
 };
 
 use super::{RunCommand, RunResult};
-use crate::{command::CommandContext, CommandComplete, LogPolicy, SolanaAccount};
+use crate::{
+    command::{complete_unsigned, identity::UpdateBatchCommandBuilder, send_tx, CommandContext},
+    CommandComplete, CommandOutput, LogClosedPolicy, LogPolicies, LogPolicy, SolanaAccount,
+};
+
+/// Maximum number of accounts fetched by a single `getMultipleAccounts` request.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 
 #[derive(Debug)]
 pub enum PolicyVersion {
     V1(Policy),
     V2(PolicyV2),
+    V3(PolicyV3),
 }
 
 impl PolicyVersion {
@@ -35,8 +50,91 @@ impl PolicyVersion {
         match self {
             PolicyVersion::V1(pv1) => pv1.strategy,
             PolicyVersion::V2(pv2) => pv2.strategy,
+            PolicyVersion::V3(pv3) => pv3.strategy,
+        }
+    }
+
+    /// The revision of the policy, only recorded by `PolicyV3` accounts.
+    pub fn revision(&self) -> Option<u64> {
+        match self {
+            PolicyVersion::V1(_) | PolicyVersion::V2(_) => None,
+            PolicyVersion::V3(pv3) => Some(u64::from_le_bytes(pv3.revision)),
         }
     }
+
+    /// The token balance required to mutate the policy, only recorded by `PolicyV3` accounts.
+    pub fn min_balance(&self) -> Option<u64> {
+        match self {
+            PolicyVersion::V1(_) | PolicyVersion::V2(_) => None,
+            PolicyVersion::V3(pv3) => Some(u64::from_le_bytes(pv3.min_balance)),
+        }
+    }
+
+    /// Whether the policy was locked, only `PolicyV3` accounts can be locked.
+    pub fn locked(&self) -> bool {
+        match self {
+            PolicyVersion::V1(_) | PolicyVersion::V2(_) => false,
+            PolicyVersion::V3(pv3) => pv3.locked != 0,
+        }
+    }
+
+    /// Decodes the header of a policy account of any version.
+    pub fn try_from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        let Some(kind) = data.first() else {
+            bail!("Policy account data is empty");
+        };
+
+        Ok(match Kind::try_from_slice(&[*kind])? {
+            Kind::Policy => PolicyVersion::V1(Policy::from_bytes(data)?),
+            Kind::PolicyV2 => PolicyVersion::V2(PolicyV2::from_bytes(data)?),
+            Kind::PolicyV3 => PolicyVersion::V3(PolicyV3::from_bytes(data)?),
+        })
+    }
+}
+
+/// Fetches the policy bound to `mint` and the token metadata of the mint.
+pub async fn fetch_policy(
+    client: &RpcClient,
+    mint: &Pubkey,
+) -> anyhow::Result<(PolicyVersion, TokenMetadata)> {
+    let (policy, _, token_metadata) = fetch_policy_with_identities(client, mint).await?;
+
+    Ok((policy, token_metadata))
+}
+
+/// Fetches the policy bound to `mint`, its identities buffer including empty slots and the
+/// token metadata of the mint.
+pub async fn fetch_policy_with_identities(
+    client: &RpcClient,
+    mint: &Pubkey,
+) -> anyhow::Result<(PolicyVersion, Vec<Pubkey>, TokenMetadata)> {
+    // PDA seeds are same for every policy version
+    let (address, _) = Policy::find_pda(mint);
+
+    let account = client.get_account(&address).await?;
+    let policy = PolicyVersion::try_from_bytes(&account.data)?;
+    let identities = DecodedPolicy::try_from_bytes(&account.data)?.identities;
+
+    let mint_data = client.get_account(mint).await?;
+    let mint_pod = PodStateWithExtensions::<PodMint>::unpack(&mint_data.data)?;
+    let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>()?;
+    let token_metadata = TokenMetadata::try_from_slice(mint_bytes)?;
+
+    Ok((policy, identities, token_metadata))
+}
+
+/// A policy as listed by `policy list`.
+#[derive(Debug)]
+pub struct PolicySummary {
+    pub address: Pubkey,
+    /// The mint bound to the policy, unknown for `Policy` (V1) accounts.
+    pub mint: Option<Pubkey>,
+    pub metadata: Option<TokenMetadata>,
+    pub strategy: PermissionStrategy,
+    /// Number of identities in the policy, ignoring empty slots.
+    pub identities_len: usize,
+    /// Version of the account, anything but `PolicyV3` can be upgraded by `policy migrate`.
+    pub kind: Kind,
 }
 
 /// Builder for creating a new policy
@@ -45,6 +143,8 @@ pub struct CreateCommandBuilder {
     name: Option<String>,
     symbol: Option<String>,
     uri: Option<String>,
+    fields: Vec<(String, String)>,
+    min_balance: Option<u64>,
 }
 
 impl Default for CreateCommandBuilder {
@@ -61,6 +161,8 @@ impl CreateCommandBuilder {
             name: None,
             symbol: None,
             uri: None,
+            fields: Vec::new(),
+            min_balance: None,
         }
     }
 
@@ -87,19 +189,37 @@ impl CreateCommandBuilder {
         self.uri = Some(uri);
         self
     }
+
+    /// Set the additional metadata fields, see `metadata_keys` for the fields describing
+    /// the policy
+    pub fn fields(mut self, fields: Vec<(String, String)>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Set the token balance required to mutate the policy
+    pub fn min_balance(mut self, min_balance: Option<u64>) -> Self {
+        self.min_balance = min_balance;
+        self
+    }
 }
 
 #[async_trait::async_trait]
 impl RunCommand for CreateCommandBuilder {
     /// Execute the creation of the policy
     async fn run(&mut self, context: CommandContext) -> RunResult {
-        let CommandContext { keypair, client } = context;
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
 
         // Given a PDA derived from the payer's public key.
         let mint = Keypair::new();
         // Create a token account for the payer.
         let payer_token_account = get_associated_token_address_with_program_id(
-            &keypair.pubkey(),
+            &authority.pubkey(),
             &mint.pubkey(),
             &spl_token_2022::ID,
         );
@@ -110,18 +230,20 @@ impl RunCommand for CreateCommandBuilder {
                 .unwrap();
 
         let token_metadata = TokenMetadata {
-            update_authority: OptionalNonZeroPubkey::try_from(Some(keypair.pubkey())).unwrap(),
+            update_authority: OptionalNonZeroPubkey::try_from(Some(authority.pubkey())).unwrap(),
             mint: mint.pubkey(),
             name: self.name.clone().expect("name must be set"),
             symbol: self.symbol.clone().expect("symbol must be set"),
             uri: self.uri.clone().expect("uri must be set"),
-            additional_metadata: Vec::<(String, String)>::new(),
+            additional_metadata: self.fields.clone(),
         };
 
+        // The rent covers the additional fields so the token program can grow the mint when
+        // they are written.
         let rent = mint_size + token_metadata.tlv_size_of().unwrap();
 
         let create_mint_ix = CreateAccountBuilder::build()
-            .payer(&keypair.pubkey())
+            .payer(&authority.pubkey())
             .account(&mint.pubkey())
             .space(mint_size)
             .rent(rent)
@@ -132,19 +254,19 @@ impl RunCommand for CreateCommandBuilder {
         let init_metadata_pointer_ix = MetadataPointerInitializeBuilder::build()
             .mint(&mint.pubkey())
             .metadata(mint.pubkey())
-            .authority(keypair.pubkey())
+            .authority(authority.pubkey())
             .instruction();
 
         let init_mint_ix = InitializeMint2Builder::build()
             .mint(&mint.pubkey())
-            .mint_authority(&keypair.pubkey())
+            .mint_authority(&authority.pubkey())
             .instruction();
 
         let init_metadata_ix = InitializeMetadataBuilder::new()
             .mint(&mint.pubkey())
-            .owner(&keypair.pubkey())
-            .update_authority(&keypair.pubkey())
-            .mint_authority(&keypair.pubkey())
+            .owner(&authority.pubkey())
+            .update_authority(&authority.pubkey())
+            .mint_authority(&authority.pubkey())
             .name(token_metadata.name)
             .symbol(token_metadata.symbol)
             .uri(token_metadata.uri)
@@ -153,77 +275,82 @@ impl RunCommand for CreateCommandBuilder {
         // Create the policy account.
         // PDA seeds are same for both Policy and PolicyV2
         let (address, _) = Policy::find_pda(&mint.pubkey());
-        let create_policy_ix = CreatePolicyBuilder::new()
+        let mut create_policy = CreatePolicyBuilder::new();
+        create_policy
             .policy(address)
             .mint(mint.pubkey())
-            .payer(keypair.pubkey())
-            .owner(keypair.pubkey())
+            .payer(authority.pubkey())
+            .owner(authority.pubkey())
             .token_account(payer_token_account)
-            .strategy(self.strategy.expect("strategy must be set"))
-            .instruction();
+            .strategy(self.strategy.expect("strategy must be set"));
+        if let Some(min_balance) = self.min_balance {
+            create_policy.min_balance(min_balance);
+        }
+        let create_policy_ix = create_policy.instruction();
 
         // Initialize the payer's token account.
         let init_payer_token_account_ix = CreateAsscoiatedTokenAccountBuilder::build()
-            .owner(&keypair.pubkey())
+            .owner(&authority.pubkey())
             .mint(&mint.pubkey())
-            .payer(&keypair.pubkey())
+            .payer(&authority.pubkey())
             .instruction();
 
-        // Mint 1 token to the payer's token account.
+        // Mint the payer enough tokens to meet the minimum balance, at least 1.
         let mint_to_payer_ix = TokenExtensionsMintToBuilder::build()
             .mint(&mint.pubkey())
             .account(&payer_token_account)
-            .owner(&keypair.pubkey())
-            .amount(1)
+            .owner(&authority.pubkey())
+            .amount(self.min_balance.unwrap_or_default().max(1))
             .instruction();
 
-        let last_blockhash = client.get_latest_blockhash().await?;
-
-        let tx = TransactionBuilder::build()
-            .instruction(create_mint_ix)
-            .instruction(init_metadata_pointer_ix)
-            .instruction(init_mint_ix)
-            .instruction(init_metadata_ix)
-            .instruction(init_payer_token_account_ix)
-            .instruction(mint_to_payer_ix)
-            .instruction(create_policy_ix)
-            .signer(&keypair)
-            .signer(&mint)
-            .payer(&keypair.pubkey())
-            .recent_blockhash(last_blockhash)
-            .transaction();
-
-        let signature = client
-            .send_and_confirm_transaction_with_spinner_and_commitment(
-                &tx,
-                CommitmentConfig::confirmed(),
-            )
-            .await?;
-
-        info!("Transaction signature: {}", signature);
+        let mut transactions = send_tx(
+            &client,
+            &authority,
+            &send_config,
+            vec![
+                create_mint_ix,
+                init_metadata_pointer_ix,
+                init_mint_ix,
+                init_metadata_ix,
+                init_payer_token_account_ix,
+                mint_to_payer_ix,
+                create_policy_ix,
+            ],
+            &[&mint],
+        )
+        .await?;
 
-        let account_data = client.get_account(&address).await?;
-        let account_data: &[u8] = &account_data.data;
+        // The fields are written in a transaction of their own, they would not fit in the
+        // one creating the mint.
+        if !self.fields.is_empty() {
+            let update_fields_ixs = self
+                .fields
+                .iter()
+                .map(|(key, value)| {
+                    UpdateMetadataFieldBuilder::build()
+                        .mint(&mint.pubkey())
+                        .update_authority(&authority.pubkey())
+                        .field(Field::Key(key.clone()))
+                        .value(value.clone())
+                        .instruction()
+                })
+                .collect();
 
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
-
-        let policy = match policy_version {
-            Kind::Policy => PolicyVersion::V1(Policy::from_bytes(&account_data[..Policy::LEN])?),
-            Kind::PolicyV2 => {
-                PolicyVersion::V2(PolicyV2::from_bytes(&account_data[..PolicyV2::LEN])?)
-            }
-        };
+            transactions
+                .extend(send_tx(&client, &authority, &send_config, update_fields_ixs, &[]).await?);
+        }
 
-        let mint_data = client.get_account(&mint.pubkey()).await?;
-        let mint_account_data: &[u8] = &mint_data.data;
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
 
-        let mint_pod = PodStateWithExtensions::<PodMint>::unpack(mint_account_data).unwrap();
-        let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>().unwrap();
-        let token_metadata = TokenMetadata::try_from_slice(mint_bytes).unwrap();
+        let (policy, token_metadata) = fetch_policy(&client, &mint.pubkey()).await?;
 
-        LogPolicy::new(&mint.pubkey(), &token_metadata, &address, &policy, None).log();
+        LogPolicy::new(&mint.pubkey(), &token_metadata, &address, &policy, None)
+            .signatures(&transactions.signatures)
+            .print(output);
 
-        Ok(CommandComplete(
+        Ok(CommandComplete::Policy(
             SolanaAccount(mint.pubkey(), Some(token_metadata)),
             SolanaAccount(address, Some(policy)),
         ))
@@ -258,13 +385,18 @@ impl<'a> DeleteCommandBuilder<'a> {
 impl RunCommand for DeleteCommandBuilder<'_> {
     /// Execute the deletion of a policy
     async fn run(&mut self, context: CommandContext) -> RunResult {
-        let CommandContext { keypair, client } = context;
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
 
         let mint = self.mint.expect("mint must be set");
         // PDA seeds are same for both Policy and PolicyV2
         let (address, _) = Policy::find_pda(mint);
         let payer_token_account = get_associated_token_address_with_program_id(
-            &keypair.pubkey(),
+            &authority.pubkey(),
             mint,
             &spl_token_2022::ID,
         );
@@ -272,30 +404,21 @@ impl RunCommand for DeleteCommandBuilder<'_> {
         let close_policy = ClosePolicyBuilder::new()
             .policy(address)
             .mint(*mint)
-            .payer(keypair.pubkey())
-            .owner(keypair.pubkey())
+            .payer(authority.pubkey())
+            .owner(authority.pubkey())
             .token_account(payer_token_account)
             .instruction();
 
-        let last_blockhash = client.get_latest_blockhash().await?;
-
-        let tx = TransactionBuilder::build()
-            .instruction(close_policy)
-            .signer(&keypair)
-            .payer(&keypair.pubkey())
-            .recent_blockhash(last_blockhash)
-            .transaction();
+        let transactions =
+            send_tx(&client, &authority, &send_config, vec![close_policy], &[]).await?;
 
-        let signature = client
-            .send_and_confirm_transaction_with_spinner_and_commitment(
-                &tx,
-                CommitmentConfig::confirmed(),
-            )
-            .await?;
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
 
-        info!("Transaction signature: {}", signature);
+        LogClosedPolicy::new(mint, &address, &transactions.signatures).print(output);
 
-        Ok(CommandComplete(
+        Ok(CommandComplete::Policy(
             SolanaAccount(*mint, None),
             SolanaAccount(address, None),
         ))
@@ -326,39 +449,927 @@ impl<'a> ShowCommandBuilder<'a> {
 #[async_trait::async_trait]
 impl RunCommand for ShowCommandBuilder<'_> {
     async fn run(&mut self, context: CommandContext) -> RunResult {
-        let CommandContext { keypair: _, client } = context;
+        let CommandContext {
+            authority: _,
+            client,
+            output,
+            send_config: _,
+        } = context;
 
         let mint = self.mint.expect("mint must be set");
         // PDA seeds are same for both Policy and PolicyV2
         let (address, _) = Policy::find_pda(mint);
 
-        let account_data = client.get_account(&address).await?;
-        let account_data: &[u8] = &account_data.data;
+        let (policy, identities, token_metadata) =
+            fetch_policy_with_identities(&client, mint).await?;
 
-        let policy_version = Kind::try_from_slice(&[account_data[0]])?;
+        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities)).print(output);
 
-        let policy = match policy_version {
-            Kind::Policy => PolicyVersion::V1(Policy::from_bytes(account_data)?),
-            Kind::PolicyV2 => PolicyVersion::V2(PolicyV2::from_bytes(account_data)?),
-        };
+        Ok(CommandComplete::Policy(
+            SolanaAccount(*mint, Some(token_metadata)),
+            SolanaAccount(address, Some(policy)),
+        ))
+    }
+}
+
+/// Builder for listing the policies of the program
+pub struct ListCommandBuilder {
+    owned: bool,
+    legacy: bool,
+}
+
+impl Default for ListCommandBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListCommandBuilder {
+    /// Create a new ListCommandBuilder
+    pub fn new() -> Self {
+        Self {
+            owned: false,
+            legacy: false,
+        }
+    }
+
+    /// Only keep policies whose mint is held by the authority
+    pub fn owned(mut self, owned: bool) -> Self {
+        self.owned = owned;
+        self
+    }
+
+    /// Only keep policies that are not yet migrated to the latest version
+    pub fn legacy(mut self, legacy: bool) -> Self {
+        self.legacy = legacy;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for ListCommandBuilder {
+    /// Execute the listing of policies
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config: _,
+        } = context;
+
+        let accounts = client
+            .get_program_accounts_with_config(
+                &yellowstone_shield_client::ID,
+                RpcProgramAccountsConfig::default(),
+            )
+            .await?;
+
+        let mut policies: Vec<(Pubkey, DecodedPolicy)> = accounts
+            .into_iter()
+            .filter_map(|(address, account)| {
+                DecodedPolicy::try_from_bytes(&account.data)
+                    .ok()
+                    .map(|policy| (address, policy))
+            })
+            .collect();
+
+        if self.legacy {
+            policies.retain(|(_, policy)| policy.kind != Kind::PolicyV3);
+        }
+
+        if self.owned {
+            let mints: Vec<Pubkey> = policies.iter().filter_map(|(_, p)| p.mint).collect();
+            let token_accounts: Vec<Pubkey> = mints
+                .iter()
+                .map(|mint| {
+                    get_associated_token_address_with_program_id(
+                        &authority.pubkey(),
+                        mint,
+                        &spl_token_2022::ID,
+                    )
+                })
+                .collect();
+
+            let mut held = HashSet::new();
+            for (mints, token_accounts) in mints
+                .chunks(MAX_MULTIPLE_ACCOUNTS)
+                .zip(token_accounts.chunks(MAX_MULTIPLE_ACCOUNTS))
+            {
+                let accounts = client.get_multiple_accounts(token_accounts).await?;
+                for (mint, account) in mints.iter().zip(accounts) {
+                    let amount = account.and_then(|account| {
+                        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                            .ok()
+                            .map(|state| state.base.amount)
+                    });
+                    if amount.unwrap_or_default() > 0 {
+                        held.insert(*mint);
+                    }
+                }
+            }
+
+            policies.retain(|(_, policy)| policy.mint.is_some_and(|mint| held.contains(&mint)));
+        }
+
+        let mut metadata = HashMap::new();
+        let mints: Vec<Pubkey> = policies.iter().filter_map(|(_, p)| p.mint).collect();
+        for mints in mints.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = client.get_multiple_accounts(mints).await?;
+            for (mint, account) in mints.iter().zip(accounts) {
+                let token_metadata = account.and_then(|account| {
+                    let mint_pod = PodStateWithExtensions::<PodMint>::unpack(&account.data).ok()?;
+                    let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>().ok()?;
+                    TokenMetadata::try_from_slice(mint_bytes).ok()
+                });
+                if let Some(token_metadata) = token_metadata {
+                    metadata.insert(*mint, token_metadata);
+                }
+            }
+        }
+
+        let mut summaries: Vec<PolicySummary> = policies
+            .into_iter()
+            .map(|(address, policy)| PolicySummary {
+                address,
+                mint: policy.mint,
+                metadata: policy.mint.and_then(|mint| metadata.remove(&mint)),
+                strategy: policy.strategy,
+                identities_len: policy.active_identities().count(),
+                kind: policy.kind,
+            })
+            .collect();
+
+        summaries.sort_by(|a, b| {
+            let name = |s: &PolicySummary| s.metadata.as_ref().map(|m| m.name.clone());
+            name(a).cmp(&name(b)).then(a.address.cmp(&b.address))
+        });
+
+        LogPolicies::new(&summaries).print(output);
+
+        Ok(CommandComplete::Policies(summaries))
+    }
+}
+
+/// Builder for migrating a legacy policy to the latest version
+pub struct MigrateCommandBuilder<'a> {
+    mint: Option<&'a Pubkey>,
+}
+
+impl Default for MigrateCommandBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> MigrateCommandBuilder<'a> {
+    /// Create a new MigrateCommandBuilder
+    pub fn new() -> Self {
+        Self { mint: None }
+    }
+
+    /// Set the mint of the policy to migrate
+    pub fn mint(mut self, mint: &'a Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for MigrateCommandBuilder<'_> {
+    /// Execute the migration of the policy to a `PolicyV3` account
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
+
+        let mint = self.mint.expect("mint must be set");
+        // PDA seeds are same for every policy version
+        let (address, _) = Policy::find_pda(mint);
+
+        let account = client.get_account(&address).await?;
+        if DecodedPolicy::try_from_bytes(&account.data)?.kind == Kind::PolicyV3 {
+            bail!("Policy {} is already at the latest version", address);
+        }
+
+        let token_account = get_associated_token_address_with_program_id(
+            &authority.pubkey(),
+            mint,
+            &spl_token_2022::ID,
+        );
+
+        let migrate_policy = MigratePolicyBuilder::new()
+            .policy(address)
+            .mint(*mint)
+            .token_account(token_account)
+            .payer(authority.pubkey())
+            .owner(authority.pubkey())
+            .instruction();
+
+        let transactions =
+            send_tx(&client, &authority, &send_config, vec![migrate_policy], &[]).await?;
+
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
+
+        let (policy, identities, token_metadata) =
+            fetch_policy_with_identities(&client, mint).await?;
+
+        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities))
+            .signatures(&transactions.signatures)
+            .print(output);
+
+        Ok(CommandComplete::Policy(
+            SolanaAccount(*mint, Some(token_metadata)),
+            SolanaAccount(address, Some(policy)),
+        ))
+    }
+}
+
+/// Builder for updating the token metadata of a policy
+pub struct UpdateMetadataCommandBuilder<'a> {
+    mint: Option<&'a Pubkey>,
+    name: Option<String>,
+    symbol: Option<String>,
+    uri: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl Default for UpdateMetadataCommandBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> UpdateMetadataCommandBuilder<'a> {
+    /// Create a new UpdateMetadataCommandBuilder
+    pub fn new() -> Self {
+        Self {
+            mint: None,
+            name: None,
+            symbol: None,
+            uri: None,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set the mint of the policy to update
+    pub fn mint(mut self, mint: &'a Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// Set the new name for the token metadata
+    pub fn name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Set the new symbol for the token metadata
+    pub fn symbol(mut self, symbol: Option<String>) -> Self {
+        self.symbol = symbol;
+        self
+    }
+
+    /// Set the new URI for the token metadata
+    pub fn uri(mut self, uri: Option<String>) -> Self {
+        self.uri = uri;
+        self
+    }
+
+    /// Set the additional metadata fields to add or overwrite
+    pub fn fields(mut self, fields: Vec<(String, String)>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// The metadata fields to update, in the order they are sent.
+    fn updates(&self) -> Vec<(Field, String)> {
+        let mut updates = Vec::new();
+
+        if let Some(name) = &self.name {
+            updates.push((Field::Name, name.clone()));
+        }
+        if let Some(symbol) = &self.symbol {
+            updates.push((Field::Symbol, symbol.clone()));
+        }
+        if let Some(uri) = &self.uri {
+            updates.push((Field::Uri, uri.clone()));
+        }
+        updates.extend(
+            self.fields
+                .iter()
+                .map(|(key, value)| (Field::Key(key.clone()), value.clone())),
+        );
+
+        updates
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for UpdateMetadataCommandBuilder<'_> {
+    /// Execute the update of the token metadata
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
+
+        let mint = self.mint.expect("mint must be set");
+        let updates = self.updates();
+        if updates.is_empty() {
+            bail!("Nothing to update, set at least one of --name, --symbol, --uri or --field");
+        }
+
+        let mint_data = client.get_account(mint).await?;
+        let mint_pod = PodStateWithExtensions::<PodMint>::unpack(&mint_data.data)?;
+        let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>()?;
+        let mut token_metadata = TokenMetadata::try_from_slice(mint_bytes)?;
+
+        // The token program reallocates the mint to fit the updated metadata but does not
+        // fund it, so the rent for the new size is transferred first.
+        let current_size = token_metadata.tlv_size_of()?;
+        for (field, value) in &updates {
+            token_metadata.update(field.clone(), value.clone());
+        }
+        let space = mint_data.data.len() - current_size + token_metadata.tlv_size_of()?;
+
+        let mut instructions: Vec<_> = RentTopUpBuilder::build()
+            .payer(&authority.pubkey())
+            .account(mint)
+            .lamports(mint_data.lamports)
+            .space(space)
+            .instruction()
+            .into_iter()
+            .collect();
+
+        instructions.extend(updates.into_iter().map(|(field, value)| {
+            UpdateMetadataFieldBuilder::build()
+                .mint(mint)
+                .update_authority(&authority.pubkey())
+                .field(field)
+                .value(value)
+                .instruction()
+        }));
+
+        let transactions = send_tx(&client, &authority, &send_config, instructions, &[]).await?;
+
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
+
+        // PDA seeds are same for every policy version
+        let (address, _) = Policy::find_pda(mint);
+
+        let (policy, token_metadata) = fetch_policy(&client, mint).await?;
+
+        LogPolicy::new(mint, &token_metadata, &address, &policy, None)
+            .signatures(&transactions.signatures)
+            .print(output);
+
+        Ok(CommandComplete::Policy(
+            SolanaAccount(*mint, Some(token_metadata)),
+            SolanaAccount(address, Some(policy)),
+        ))
+    }
+}
 
-        let identities = match policy_version {
-            Kind::Policy => Policy::try_deserialize_identities(account_data)?,
-            Kind::PolicyV2 => PolicyV2::try_deserialize_identities(account_data)?,
+/// Builder for handing a policy to another owner
+pub struct TransferCommandBuilder<'a> {
+    mint: Option<&'a Pubkey>,
+    to: Option<&'a Pubkey>,
+    revoke_mint_authority: bool,
+}
+
+impl Default for TransferCommandBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> TransferCommandBuilder<'a> {
+    /// Create a new TransferCommandBuilder
+    pub fn new() -> Self {
+        Self {
+            mint: None,
+            to: None,
+            revoke_mint_authority: false,
+        }
+    }
+
+    /// Set the mint of the policy to transfer
+    pub fn mint(mut self, mint: &'a Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// Set the new owner of the policy
+    pub fn to(mut self, to: &'a Pubkey) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Remove the mint authority so no more policy tokens can be minted
+    pub fn revoke_mint_authority(mut self, revoke_mint_authority: bool) -> Self {
+        self.revoke_mint_authority = revoke_mint_authority;
+        self
+    }
+}
+
+/// Checks that `owner` passes the token account checks of the program for the policy of
+/// `mint`: its associated token account holds a positive amount of the mint, and at least
+/// the `min_balance` of the policy.
+///
+/// Returns the amount held.
+fn assert_policy_owner(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_account: Option<&[u8]>,
+    min_balance: u64,
+) -> anyhow::Result<u64> {
+    let Some(data) = token_account else {
+        bail!("{} has no token account for mint {}", owner, mint);
+    };
+    let account = StateWithExtensions::<TokenAccount>::unpack(data)?.base;
+
+    if account.mint != *mint || account.owner != *owner || account.amount == 0 {
+        bail!("{} does not hold the policy token of mint {}", owner, mint);
+    }
+
+    if account.amount < min_balance {
+        bail!(
+            "{} holds {} policy token(s) of mint {}, below the minimum balance of {}",
+            owner,
+            account.amount,
+            mint,
+            min_balance
+        );
+    }
+
+    Ok(account.amount)
+}
+
+#[async_trait::async_trait]
+impl RunCommand for TransferCommandBuilder<'_> {
+    /// Execute the transfer of the policy token to the new owner
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
+
+        let mint = self.mint.expect("mint must be set");
+        let to = self.to.expect("to must be set");
+        if *to == authority.pubkey() {
+            bail!("The policy is already owned by {}", to);
+        }
+
+        let source = get_associated_token_address_with_program_id(
+            &authority.pubkey(),
+            mint,
+            &spl_token_2022::ID,
+        );
+        let destination =
+            get_associated_token_address_with_program_id(to, mint, &spl_token_2022::ID);
+
+        // PDA seeds are same for every policy version
+        let (address, _) = Policy::find_pda(mint);
+
+        // Policies without a minimum balance accept any positive balance.
+        let account = client.get_account(&address).await?;
+        let min_balance = DecodedPolicy::try_from_bytes(&account.data)?
+            .min_balance
+            .unwrap_or_default();
+
+        let source_data = client
+            .get_account(&source)
+            .await
+            .ok()
+            .map(|account| account.data);
+        let amount = assert_policy_owner(
+            &authority.pubkey(),
+            mint,
+            source_data.as_deref(),
+            min_balance,
+        )?;
+
+        let mint_data = client.get_account(mint).await?;
+        let decimals = StateWithExtensions::<Mint>::unpack(&mint_data.data)?
+            .base
+            .decimals;
+
+        // The recipient may already hold an account for the mint.
+        let create_destination_ix = CreateAsscoiatedTokenAccountBuilder::build()
+            .owner(to)
+            .mint(mint)
+            .payer(&authority.pubkey())
+            .idempotent(true)
+            .instruction();
+
+        // The whole balance is moved so the previous owner no longer passes the token checks.
+        let transfer_ix = TokenExtensionsTransferBuilder::build()
+            .mint(mint)
+            .source(&source)
+            .destination(&destination)
+            .owner(&authority.pubkey())
+            .amount(amount)
+            .decimals(decimals)
+            .instruction();
+
+        let mut instructions = vec![create_destination_ix, transfer_ix];
+        if self.revoke_mint_authority {
+            instructions.push(
+                RevokeMintAuthorityBuilder::build()
+                    .mint(mint)
+                    .mint_authority(&authority.pubkey())
+                    .instruction(),
+            );
+        }
+
+        let transactions = send_tx(&client, &authority, &send_config, instructions, &[]).await?;
+
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
+
+        let destination_data = client
+            .get_account(&destination)
+            .await
+            .ok()
+            .map(|account| account.data);
+        assert_policy_owner(to, mint, destination_data.as_deref(), min_balance)?;
+
+        let (policy, token_metadata) = fetch_policy(&client, mint).await?;
+
+        LogPolicy::new(mint, &token_metadata, &address, &policy, None)
+            .signatures(&transactions.signatures)
+            .print(output);
+
+        Ok(CommandComplete::Policy(
+            SolanaAccount(*mint, Some(token_metadata)),
+            SolanaAccount(address, Some(policy)),
+        ))
+    }
+}
+
+/// Minimum balance of a policy, as an amount of tokens or a share of the supply
+#[derive(Debug, Clone, Copy)]
+pub enum MinBalance {
+    Amount(u64),
+    /// Percent of the mint supply, rounded up to a whole token.
+    Percent(u8),
+}
+
+/// Builder for setting the token balance required to mutate a policy
+pub struct SetMinBalanceCommandBuilder<'a> {
+    mint: Option<&'a Pubkey>,
+    min_balance: Option<MinBalance>,
+}
+
+impl Default for SetMinBalanceCommandBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SetMinBalanceCommandBuilder<'a> {
+    /// Create a new SetMinBalanceCommandBuilder
+    pub fn new() -> Self {
+        Self {
+            mint: None,
+            min_balance: None,
+        }
+    }
+
+    /// Set the mint of the policy to update
+    pub fn mint(mut self, mint: &'a Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// Set the minimum balance
+    pub fn min_balance(mut self, min_balance: MinBalance) -> Self {
+        self.min_balance = Some(min_balance);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for SetMinBalanceCommandBuilder<'_> {
+    /// Execute the update of the minimum balance of the policy
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
+
+        let mint = self.mint.expect("mint must be set");
+        // PDA seeds are same for every policy version
+        let (address, _) = Policy::find_pda(mint);
+
+        let account = client.get_account(&address).await?;
+        let Some(revision) = DecodedPolicy::try_from_bytes(&account.data)?.revision else {
+            bail!(
+                "Policy {} has no minimum balance, run `policy migrate` first",
+                address
+            );
         };
 
         let mint_data = client.get_account(mint).await?;
-        let account_data: &[u8] = &mint_data.data;
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_data.data)?;
 
-        let mint_pod = PodStateWithExtensions::<PodMint>::unpack(account_data).unwrap();
-        let mint_bytes = mint_pod.get_extension_bytes::<TokenMetadata>().unwrap();
-        let token_metadata = TokenMetadata::try_from_slice(mint_bytes).unwrap();
+        let min_balance = match self.min_balance.expect("min_balance must be set") {
+            MinBalance::Amount(amount) => amount,
+            MinBalance::Percent(percent) => {
+                (mint_state.base.supply as u128 * percent as u128).div_ceil(100) as u64
+            }
+        };
 
-        LogPolicy::new(mint, &token_metadata, &address, &policy, Some(&identities)).log();
+        let token_account = get_associated_token_address_with_program_id(
+            &authority.pubkey(),
+            mint,
+            &spl_token_2022::ID,
+        );
 
-        Ok(CommandComplete(
+        let set_min_balance = SetMinBalanceBuilder::new()
+            .policy(address)
+            .mint(*mint)
+            .token_account(token_account)
+            .owner(authority.pubkey())
+            .min_balance(min_balance)
+            .expected_revision(revision)
+            .instruction();
+
+        let transactions = send_tx(
+            &client,
+            &authority,
+            &send_config,
+            vec![set_min_balance],
+            &[],
+        )
+        .await?;
+
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
+
+        let (policy, token_metadata) = fetch_policy(&client, mint).await?;
+
+        LogPolicy::new(mint, &token_metadata, &address, &policy, None)
+            .signatures(&transactions.signatures)
+            .print(output);
+
+        Ok(CommandComplete::Policy(
             SolanaAccount(*mint, Some(token_metadata)),
             SolanaAccount(address, Some(policy)),
         ))
     }
 }
+
+/// Builder for locking a policy, making it immutable
+pub struct LockCommandBuilder<'a> {
+    mint: Option<&'a Pubkey>,
+}
+
+impl Default for LockCommandBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> LockCommandBuilder<'a> {
+    /// Create a new LockCommandBuilder
+    pub fn new() -> Self {
+        Self { mint: None }
+    }
+
+    /// Set the mint of the policy to lock
+    pub fn mint(mut self, mint: &'a Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for LockCommandBuilder<'_> {
+    /// Execute the locking of the policy
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority,
+            client,
+            output,
+            send_config,
+        } = context;
+
+        let mint = self.mint.expect("mint must be set");
+        // PDA seeds are same for every policy version
+        let (address, _) = Policy::find_pda(mint);
+
+        let account = client.get_account(&address).await?;
+        let decoded = DecodedPolicy::try_from_bytes(&account.data)?;
+        let Some(revision) = decoded.revision else {
+            bail!(
+                "Policy {} cannot be locked, run `policy migrate` first",
+                address
+            );
+        };
+        if decoded.locked {
+            bail!("Policy {} is already locked", address);
+        }
+
+        let token_account = get_associated_token_address_with_program_id(
+            &authority.pubkey(),
+            mint,
+            &spl_token_2022::ID,
+        );
+
+        let lock_policy = LockPolicyBuilder::new()
+            .policy(address)
+            .mint(*mint)
+            .token_account(token_account)
+            .owner(authority.pubkey())
+            .expected_revision(revision)
+            .instruction();
+
+        let transactions =
+            send_tx(&client, &authority, &send_config, vec![lock_policy], &[]).await?;
+
+        if authority.is_offline() {
+            return complete_unsigned(transactions.unsigned, output);
+        }
+
+        let (policy, token_metadata) = fetch_policy(&client, mint).await?;
+
+        LogPolicy::new(mint, &token_metadata, &address, &policy, None)
+            .signatures(&transactions.signatures)
+            .print(output);
+
+        Ok(CommandComplete::Policy(
+            SolanaAccount(*mint, Some(token_metadata)),
+            SolanaAccount(address, Some(policy)),
+        ))
+    }
+}
+
+/// Builder for copying the identities of a policy into another policy
+pub struct CloneCommandBuilder<'a> {
+    from_mint: Option<&'a Pubkey>,
+    from_url: Option<&'a str>,
+    to_mint: Option<&'a Pubkey>,
+    dry_run: bool,
+    yes: bool,
+}
+
+impl Default for CloneCommandBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> CloneCommandBuilder<'a> {
+    /// Create a new CloneCommandBuilder
+    pub fn new() -> Self {
+        Self {
+            from_mint: None,
+            from_url: None,
+            to_mint: None,
+            dry_run: false,
+            yes: false,
+        }
+    }
+
+    /// Set the mint of the policy the identities are copied from
+    pub fn from_mint(mut self, from_mint: &'a Pubkey) -> Self {
+        self.from_mint = Some(from_mint);
+        self
+    }
+
+    /// Set the RPC URL of the cluster holding the source policy, defaults to the command client
+    pub fn from_url(mut self, from_url: Option<&'a str>) -> Self {
+        self.from_url = from_url;
+        self
+    }
+
+    /// Set the mint of the policy the identities are copied to
+    pub fn to_mint(mut self, to_mint: &'a Pubkey) -> Self {
+        self.to_mint = Some(to_mint);
+        self
+    }
+
+    /// Print the plan without sending any transaction
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Send the transactions without asking for confirmation
+    pub fn yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for CloneCommandBuilder<'_> {
+    /// Execute the copy, updating the destination to the identities of the source
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let from_mint = self.from_mint.expect("from_mint must be set");
+        let to_mint = self.to_mint.expect("to_mint must be set");
+
+        // PDA seeds are same for both Policy and PolicyV2
+        let (from_address, _) = Policy::find_pda(from_mint);
+        let (to_address, _) = Policy::find_pda(to_mint);
+
+        let source = match self.from_url {
+            Some(url) => {
+                RpcClient::new_with_commitment(url.to_string(), context.client.commitment())
+                    .get_account(&from_address)
+                    .await?
+            }
+            None => context.client.get_account(&from_address).await?,
+        };
+        let source = DecodedPolicy::try_from_bytes(&source.data)?;
+        let destination = context.client.get_account(&to_address).await?;
+        let destination = DecodedPolicy::try_from_bytes(&destination.data)?;
+
+        // Copying a deny list into an allow list, or the reverse, inverts its meaning.
+        if source.strategy != destination.strategy {
+            bail!(
+                "Policy {} uses the {:?} strategy but policy {} uses {:?}",
+                from_address,
+                source.strategy,
+                to_address,
+                destination.strategy
+            );
+        }
+
+        let identities: Vec<Pubkey> = source.active_identities().copied().collect();
+
+        // The update plan keeps the identities both policies share and only removes,
+        // replaces and appends the difference.
+        UpdateBatchCommandBuilder::new()
+            .mint(to_mint)
+            .identities(identities)
+            .dry_run(self.dry_run)
+            .yes(self.yes)
+            .run(context)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spl_token_2022::{solana_program::program_pack::Pack, state::AccountState};
+
+    use super::*;
+
+    fn token_account(owner: &Pubkey, mint: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount::pack(
+            TokenAccount {
+                mint: *mint,
+                owner: *owner,
+                amount,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_assert_policy_owner_min_balance() {
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let data = token_account(&owner, &mint, 2);
+        assert_eq!(
+            assert_policy_owner(&owner, &mint, Some(&data), 0).unwrap(),
+            2
+        );
+        assert_eq!(
+            assert_policy_owner(&owner, &mint, Some(&data), 2).unwrap(),
+            2
+        );
+
+        let message = assert_policy_owner(&owner, &mint, Some(&data), 3)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("below the minimum balance of 3"));
+
+        let empty = token_account(&owner, &mint, 0);
+        assert!(assert_policy_owner(&owner, &mint, Some(&empty), 0).is_err());
+        assert!(assert_policy_owner(&owner, &mint, None, 0).is_err());
+        assert!(assert_policy_owner(&Pubkey::new_unique(), &mint, Some(&data), 0).is_err());
+    }
+}
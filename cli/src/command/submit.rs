@@ -0,0 +1,87 @@
+use anyhow::{bail, Context};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use log::info;
+use solana_commitment_config::CommitmentConfig;
+use solana_transaction::Transaction;
+
+use super::{RunCommand, RunResult};
+use crate::{command::CommandContext, CommandComplete, CommandOutput, LogSignatures};
+
+/// Decodes a base64, bincode serialized transaction.
+pub fn decode_transaction(encoded: &str) -> anyhow::Result<Transaction> {
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .context("invalid base64 transaction")?;
+
+    bincode::deserialize(&bytes).context("invalid transaction")
+}
+
+/// Encodes a transaction as base64, bincode serialized bytes.
+pub fn encode_transaction(transaction: &Transaction) -> String {
+    BASE64.encode(bincode::serialize(transaction).expect("transaction serializes"))
+}
+
+/// Builder for broadcasting externally signed transactions
+pub struct SubmitCommandBuilder {
+    transactions: Option<Vec<Transaction>>,
+}
+
+impl Default for SubmitCommandBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubmitCommandBuilder {
+    /// Create a new SubmitCommandBuilder
+    pub fn new() -> Self {
+        Self { transactions: None }
+    }
+
+    /// Set the signed transactions, sent in the given order
+    pub fn transactions(mut self, transactions: Vec<Transaction>) -> Self {
+        self.transactions = Some(transactions);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl RunCommand for SubmitCommandBuilder {
+    /// Execute the broadcast of the transactions
+    async fn run(&mut self, context: CommandContext) -> RunResult {
+        let CommandContext {
+            authority: _,
+            client,
+            output,
+            send_config: _,
+        } = context;
+
+        let transactions = self.transactions.take().expect("transactions must be set");
+
+        // Check every transaction before sending any, so a batch is never partially applied
+        // because a later transaction was not signed.
+        for (i, transaction) in transactions.iter().enumerate() {
+            if !transaction.is_signed() {
+                bail!("Transaction {} is missing signatures", i + 1);
+            }
+        }
+
+        let mut signatures = Vec::with_capacity(transactions.len());
+
+        for transaction in &transactions {
+            let signature = client
+                .send_and_confirm_transaction_with_spinner_and_commitment(
+                    transaction,
+                    CommitmentConfig::confirmed(),
+                )
+                .await?;
+
+            info!("Transaction signature: {}", signature);
+            signatures.push(signature);
+        }
+
+        LogSignatures::new(&signatures).print(output);
+
+        Ok(CommandComplete::Signatures(signatures))
+    }
+}
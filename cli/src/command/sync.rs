@@ -0,0 +1,382 @@
+use std::{
+    collections::BTreeSet,
+    fs::{create_dir_all, read_to_string, remove_file, rename, write},
+    io,
+    path::PathBuf,
+};
+
+use anyhow::bail;
+use futures::{stream, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use solana_sha256_hasher::hashv;
+use yellowstone_shield_client::DecodedPolicy;
+
+use super::{send_tx_with_spinner, Authority, BatchResult, SendConfig, Transactions};
+use crate::identity::IdentityPlan;
+
+/// Step of an identities plan. Steps are sent in order: removes, then replaces, then adds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Remove,
+    Replace,
+    Add,
+}
+
+/// Hash identifying the input of an identities command, used to key its checkpoint.
+pub fn input_hash(command: &str, identities: &[Pubkey]) -> String {
+    let mut vals: Vec<&[u8]> = vec![command.as_bytes()];
+    vals.extend(identities.iter().map(AsRef::as_ref));
+
+    hashv(&vals).to_string()
+}
+
+/// Progress of an identities plan, saved after every confirmed batch so a run that was
+/// interrupted resumes where it stopped instead of computing a new plan.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// File the checkpoint is saved to, `None` when progress is not recorded.
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    pub plan: IdentityPlan,
+    completed: BTreeSet<(Phase, usize)>,
+}
+
+impl Checkpoint {
+    /// Directory checkpoints are saved in, `~/.cache/shield/checkpoints`.
+    pub fn default_dir() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join(".cache")
+                .join("shield")
+                .join("checkpoints")
+        })
+    }
+
+    /// Opens the checkpoint of `input` applied to `policy`, whose identities are `current`.
+    ///
+    /// The plan recorded by an interrupted run is resumed with what `current` is still
+    /// missing, otherwise the checkpoint starts from `plan`. Nothing is written until a batch
+    /// is confirmed.
+    pub fn open(
+        policy: &Pubkey,
+        input: &str,
+        plan: IdentityPlan,
+        current: &[Pubkey],
+    ) -> io::Result<Self> {
+        let Some(dir) = Self::default_dir() else {
+            return Ok(Self::disabled(plan));
+        };
+        let path = dir.join(format!("{}-{}.json", policy, input));
+
+        if !path.exists() {
+            return Ok(Self {
+                path: Some(path),
+                plan,
+                completed: BTreeSet::new(),
+            });
+        }
+
+        let checkpoint: Checkpoint = serde_json::from_str(&read_to_string(&path)?)?;
+        info!(
+            "Resuming from checkpoint {} with {} batch(es) already confirmed",
+            path.display(),
+            checkpoint.completed.len()
+        );
+
+        // A batch can land without being recorded, when its confirmation timed out or the run
+        // stopped before saving it. Resending it would fail on every rerun, so only what the
+        // policy is still missing is sent, expecting the revision it was read at.
+        Ok(Self {
+            path: Some(path),
+            plan: checkpoint.plan.remaining(current, plan.revision),
+            completed: BTreeSet::new(),
+        })
+    }
+
+    /// A checkpoint that does not record progress.
+    pub fn disabled(plan: IdentityPlan) -> Self {
+        Self {
+            path: None,
+            plan,
+            completed: BTreeSet::new(),
+        }
+    }
+
+    fn is_completed(&self, phase: Phase, batch: usize) -> bool {
+        self.completed.contains(&(phase, batch))
+    }
+
+    fn complete(&mut self, phase: Phase, batch: usize) -> io::Result<()> {
+        self.completed.insert((phase, batch));
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        // Write then rename so an interrupted write never leaves a truncated checkpoint.
+        let tmp = path.with_extension("json.tmp");
+        write(&tmp, serde_json::to_vec(self)?)?;
+        rename(tmp, path)
+    }
+
+    fn remove(&self) -> io::Result<()> {
+        match &self.path {
+            Some(path) if path.exists() => remove_file(path),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Sends the batches of an identities plan, skipping the batches recorded in the checkpoint
+/// and showing the progress on stderr.
+///
+/// Batches of a phase are independent and sent concurrently, up to `max_in_flight` at once.
+/// A phase starts once every batch of the previous phase is confirmed.
+///
+/// When the plan is sent to a policy recording a revision, the first batch of every phase is
+/// sent alone and each instruction in it expects the revision left by the one before it, so
+/// the plan fails before the phase changes the policy if it was modified since the plan was
+/// computed. Once a phase is confirmed, the policy is read again and the plan fails if its
+/// revision is not the one the phase left.
+pub struct BatchSender<'a> {
+    client: &'a RpcClient,
+    authority: &'a Authority,
+    config: &'a SendConfig,
+    checkpoint: Checkpoint,
+    /// Revision expected by the first instruction of the next phase.
+    guard: Option<u64>,
+    chunk_size: usize,
+    progress: ProgressBar,
+    transactions: Transactions,
+}
+
+impl<'a> BatchSender<'a> {
+    pub fn new(
+        client: &'a RpcClient,
+        authority: &'a Authority,
+        config: &'a SendConfig,
+        checkpoint: Checkpoint,
+        chunk_size: usize,
+    ) -> Self {
+        let progress = if authority.is_offline() {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new(checkpoint.plan.transactions() as u64)
+        };
+        progress.set_style(
+            ProgressStyle::with_template(
+                "[{elapsed_precise}] {wide_bar} {pos}/{len} batches (ETA {eta})",
+            )
+            .expect("valid progress template"),
+        );
+        progress.set_position(checkpoint.completed.len() as u64);
+
+        let guard = checkpoint.plan.revision;
+
+        Self {
+            client,
+            authority,
+            config,
+            checkpoint,
+            guard,
+            chunk_size,
+            progress,
+            transactions: Transactions::default(),
+        }
+    }
+
+    /// Sends `items` of the `phase` in transactions of `chunk_size` instructions.
+    ///
+    /// The builder receives the revision the instruction must expect, if any.
+    ///
+    /// Fails once every batch of the phase was attempted if any of them failed, so the
+    /// following phases are never sent before the phase is complete.
+    pub async fn send<T, F>(
+        &mut self,
+        phase: Phase,
+        items: &[T],
+        mut instruction_builder: F,
+    ) -> anyhow::Result<()>
+    where
+        F: FnMut(&T, Option<u64>) -> Instruction,
+    {
+        let mut pending: Vec<(usize, Vec<Instruction>)> = items
+            .chunks(self.chunk_size)
+            .enumerate()
+            .filter(|(batch, _)| !self.checkpoint.is_completed(phase, *batch))
+            .map(|(batch, chunk)| {
+                let guard = if batch == 0 { self.guard } else { None };
+                let instructions = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| instruction_builder(item, guard.map(|r| r + i as u64)))
+                    .collect();
+                (batch, instructions)
+            })
+            .collect();
+
+        // Every instruction increments the revision of the policy once.
+        let expected_revision = self.guard.map(|revision| {
+            revision
+                + pending
+                    .iter()
+                    .map(|(_, instructions)| instructions.len() as u64)
+                    .sum::<u64>()
+        });
+
+        if self.guard.is_some() && !pending.is_empty() {
+            self.send_guarded(phase, pending.remove(0)).await?;
+        }
+
+        let (client, authority, config) = (self.client, self.authority, self.config);
+        let mut results = stream::iter(pending)
+            .map(move |(batch, instructions)| async move {
+                let result =
+                    send_tx_with_spinner(client, authority, config, instructions, &[], false).await;
+                (batch, result)
+            })
+            .buffer_unordered(config.max_in_flight);
+
+        let mut sent = Vec::new();
+        let mut failed = Vec::new();
+
+        while let Some((batch, result)) = results.next().await {
+            match result {
+                Ok(transactions) => {
+                    self.checkpoint.complete(phase, batch)?;
+                    self.progress.inc(1);
+
+                    if let Some(signature) = transactions.signatures.first() {
+                        info!("{:?} batch {} confirmed: {}", phase, batch, signature);
+                    }
+                    sent.push((batch, transactions));
+                }
+                Err(e) => {
+                    warn!("{:?} batch {} failed: {}", phase, batch, e);
+                    failed.push(format!("{:?} batch {}: {}", phase, batch, e));
+                }
+            }
+        }
+
+        // Report the batches in order, whatever order they were confirmed in.
+        sent.sort_by_key(|(batch, _)| *batch);
+        for (batch, mut transactions) in sent {
+            transactions.batches = transactions
+                .signatures
+                .iter()
+                .map(|signature| BatchResult {
+                    phase,
+                    batch,
+                    signature: *signature,
+                })
+                .collect();
+            self.transactions.extend(transactions);
+        }
+
+        if !failed.is_empty() {
+            bail!(
+                "{} batch(es) failed, rerun the command to resume: {}",
+                failed.len(),
+                failed.join("; ")
+            );
+        }
+
+        if let Some(expected_revision) = expected_revision {
+            self.guard = Some(self.check_revision(phase, expected_revision).await?);
+        }
+
+        Ok(())
+    }
+
+    /// Fails if the revision of the policy is not `expected` once the `phase` is confirmed,
+    /// meaning another transaction changed the policy while the phase was sent.
+    ///
+    /// Transactions of an offline authority are not sent, the revision is not read.
+    async fn check_revision(&self, phase: Phase, expected: u64) -> anyhow::Result<u64> {
+        if self.authority.is_offline() {
+            return Ok(expected);
+        }
+
+        let account = self
+            .client
+            .get_account(&self.checkpoint.plan.policy)
+            .await?;
+        let revision = DecodedPolicy::try_from_bytes(&account.data)?.revision;
+
+        if revision != Some(expected) {
+            bail!(
+                "The policy changed while the {:?} phase was sent, expected revision {} but \
+                 found {:?}, rerun the command to resume",
+                phase,
+                expected,
+                revision
+            );
+        }
+
+        Ok(expected)
+    }
+
+    /// Sends the first batch of a phase alone, so the remaining batches are only sent once
+    /// the revision check passed.
+    async fn send_guarded(
+        &mut self,
+        phase: Phase,
+        (batch, instructions): (usize, Vec<Instruction>),
+    ) -> anyhow::Result<()> {
+        let mut transactions = send_tx_with_spinner(
+            self.client,
+            self.authority,
+            self.config,
+            instructions,
+            &[],
+            false,
+        )
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "{:?} batch {} failed, the policy may have changed since the plan was \
+                 computed, rerun the command to resume: {}",
+                phase,
+                batch,
+                e
+            )
+        })?;
+
+        self.checkpoint.complete(phase, batch)?;
+        self.progress.inc(1);
+
+        if let Some(signature) = transactions.signatures.first() {
+            info!("{:?} batch {} confirmed: {}", phase, batch, signature);
+        }
+        transactions.batches = transactions
+            .signatures
+            .iter()
+            .map(|signature| BatchResult {
+                phase,
+                batch,
+                signature: *signature,
+            })
+            .collect();
+        self.transactions.extend(transactions);
+
+        Ok(())
+    }
+
+    /// Returns the transactions sent, discarding the checkpoint of the completed plan.
+    pub fn finish(self) -> io::Result<Transactions> {
+        self.progress.finish_and_clear();
+        self.checkpoint.remove()?;
+
+        Ok(self.transactions)
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::HashMap,
+    fs::read_to_string as read_path,
+    io::{read_to_string, stdin},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+use clap_derive::{Args as DeriveArgs, ValueEnum};
+use solana_pubkey::Pubkey;
+
+/// Format of an identities file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// Detected from the file extension, `.json` or `.csv`, falling back to `lines`.
+    #[default]
+    Auto,
+    /// One public key per line. `#` starts a comment and blank lines are skipped.
+    Lines,
+    /// A JSON array of public keys.
+    Json,
+    /// A CSV file with a header row, the identities read from `--column`.
+    Csv,
+}
+
+/// Identities given to an identities command, read from a file, stdin or the command line.
+///
+/// Every entry must be a valid public key listed once, otherwise nothing is sent and every
+/// invalid entry is reported with its line number.
+#[derive(DeriveArgs, Debug, Clone, Default)]
+pub struct IdentitiesInput {
+    /// File with the identities, or `-` to read from stdin
+    #[arg(long, required_unless_present = "identity")]
+    pub identities_path: Option<PathBuf>,
+
+    /// An identity, may be repeated
+    #[arg(long = "identity", value_name = "PUBKEY")]
+    pub identity: Vec<Pubkey>,
+
+    /// Format of the identities file
+    #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+    pub format: InputFormat,
+
+    /// Header of the CSV column holding the identities, defaults to the first column
+    #[arg(long)]
+    pub column: Option<String>,
+}
+
+/// Where an identity was read from, used to report errors.
+#[derive(Debug, Clone, Copy)]
+enum Location {
+    Line(usize),
+    Entry(usize),
+    Argument(usize),
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Line(line) => write!(f, "line {}", line),
+            Location::Entry(entry) => write!(f, "entry {}", entry),
+            Location::Argument(argument) => write!(f, "--identity #{}", argument),
+        }
+    }
+}
+
+impl IdentitiesInput {
+    /// Reads the identities of the file followed by the `--identity` arguments.
+    pub fn read(&self) -> Result<Vec<Pubkey>> {
+        let mut entries = match &self.identities_path {
+            Some(path) => {
+                let content = if path.as_os_str() == "-" {
+                    read_to_string(stdin()).context("unable to read identities from stdin")?
+                } else {
+                    read_path(path).with_context(|| format!("unable to read {}", path.display()))?
+                };
+
+                match self.format.resolve(path, &content) {
+                    InputFormat::Json => parse_json(&content)?,
+                    InputFormat::Csv => parse_csv(&content, self.column.as_deref())?,
+                    _ => parse_lines(&content),
+                }
+            }
+            None => Vec::new(),
+        };
+
+        entries.extend(
+            self.identity
+                .iter()
+                .enumerate()
+                .map(|(i, identity)| (Location::Argument(i + 1), identity.to_string())),
+        );
+
+        validate(entries)
+    }
+
+    /// Reads the identities of a command that asks for confirmation when `prompt` is set.
+    ///
+    /// The confirmation prompt reads its answer from stdin, so identities read from stdin
+    /// require `--yes` or `--dry-run`.
+    pub fn read_with_prompt(&self, prompt: bool) -> Result<Vec<Pubkey>> {
+        if prompt && self.reads_stdin() {
+            bail!(
+                "--identities-path - reads the identities from stdin, leaving no input to \
+                 answer the confirmation prompt: pass --yes to send without confirmation, or \
+                 --dry-run to print the plan"
+            );
+        }
+
+        self.read()
+    }
+
+    fn reads_stdin(&self) -> bool {
+        self.identities_path
+            .as_ref()
+            .is_some_and(|path| path.as_os_str() == "-")
+    }
+}
+
+impl InputFormat {
+    fn from_extension(path: &Path) -> Option<InputFormat> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Some(InputFormat::Json),
+            Some("csv") => Some(InputFormat::Csv),
+            _ => None,
+        }
+    }
+
+    fn resolve(self, path: &Path, content: &str) -> InputFormat {
+        if self != InputFormat::Auto {
+            return self;
+        }
+
+        InputFormat::from_extension(path).unwrap_or(if content.trim_start().starts_with('[') {
+            InputFormat::Json
+        } else {
+            InputFormat::Lines
+        })
+    }
+}
+
+/// Formats identities so they can be read back by [`IdentitiesInput`].
+///
+/// `Auto` picks the format from the extension of `path`, falling back to `lines`.
+pub fn format_identities(
+    identities: &[Pubkey],
+    format: InputFormat,
+    path: Option<&Path>,
+) -> String {
+    let format = match format {
+        InputFormat::Auto => path
+            .and_then(InputFormat::from_extension)
+            .unwrap_or(InputFormat::Lines),
+        format => format,
+    };
+    let identities = identities.iter().map(ToString::to_string);
+
+    match format {
+        InputFormat::Json => {
+            let identities: Vec<String> = identities.collect();
+            format!("{:#}\n", serde_json::json!(identities))
+        }
+        InputFormat::Csv => std::iter::once("identity".to_string())
+            .chain(identities)
+            .map(|line| line + "\n")
+            .collect(),
+        _ => identities.map(|line| line + "\n").collect(),
+    }
+}
+
+fn parse_lines(content: &str) -> Vec<(Location, String)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let entry = line.split('#').next().unwrap_or_default().trim();
+            (!entry.is_empty()).then(|| (Location::Line(i + 1), entry.to_string()))
+        })
+        .collect()
+}
+
+fn parse_json(content: &str) -> Result<Vec<(Location, String)>> {
+    let entries: Vec<String> =
+        serde_json::from_str(content).context("expected a JSON array of public keys")?;
+
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| (Location::Entry(i + 1), entry))
+        .collect())
+}
+
+fn parse_csv(content: &str, column: Option<&str>) -> Result<Vec<(Location, String)>> {
+    let mut rows = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let Some((_, header)) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let header = split_csv_record(header);
+    let index = match column {
+        Some(column) => header
+            .iter()
+            .position(|name| name == column)
+            .with_context(|| format!("column {:?} not found in the CSV header", column))?,
+        None => 0,
+    };
+
+    rows.map(|(i, line)| {
+        let record = split_csv_record(line);
+        let field = record
+            .get(index)
+            .with_context(|| format!("line {}: missing column {}", i + 1, index + 1))?;
+
+        Ok((Location::Line(i + 1), field.clone()))
+    })
+    .collect()
+}
+
+/// Splits a CSV record on commas, honouring double quoted fields.
+fn split_csv_record(record: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+
+    fields
+}
+
+/// Parses every entry, failing with every invalid or repeated entry.
+///
+/// The default public key is invalid, the program rejects it as an identity.
+fn validate(entries: Vec<(Location, String)>) -> Result<Vec<Pubkey>> {
+    let mut identities = Vec::with_capacity(entries.len());
+    let mut seen: HashMap<Pubkey, Location> = HashMap::with_capacity(entries.len());
+    let mut errors = Vec::new();
+
+    for (location, entry) in entries {
+        match Pubkey::from_str(&entry) {
+            Ok(identity) if identity == Pubkey::default() => errors.push(format!(
+                "{}: invalid identity {} (the default public key)",
+                location, identity
+            )),
+            Ok(identity) => match seen.get(&identity) {
+                Some(first) => errors.push(format!(
+                    "{}: duplicate identity {} (first listed on {})",
+                    location, identity, first
+                )),
+                None => {
+                    seen.insert(identity, location);
+                    identities.push(identity);
+                }
+            },
+            Err(_) => errors.push(format!("{}: invalid public key {:?}", location, entry)),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!(
+            "{} invalid identities, nothing was sent:\n{}",
+            errors.len(),
+            errors.join("\n")
+        );
+    }
+
+    Ok(identities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(count: usize) -> Vec<Pubkey> {
+        (0..count).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    fn errors(result: Result<Vec<Pubkey>>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let [a, b] = keys(2)[..] else { unreachable!() };
+        let content = format!("# allowed validators\n{a}\n\n  {b}  # second\n");
+
+        assert_eq!(validate(parse_lines(&content)).unwrap(), vec![a, b]);
+    }
+
+    #[test]
+    fn test_parse_lines_reports_line_numbers() {
+        let a = Pubkey::new_unique();
+        let content = format!("{a}\nnot-a-key\n# comment\n{a}\n");
+
+        let message = errors(validate(parse_lines(&content)));
+
+        assert!(message.starts_with("2 invalid identities"));
+        assert!(message.contains("line 2: invalid public key \"not-a-key\""));
+        assert!(message.contains(&format!(
+            "line 4: duplicate identity {a} (first listed on line 1)"
+        )));
+    }
+
+    #[test]
+    fn test_reject_default_identity() {
+        let a = Pubkey::new_unique();
+        let content = format!("{a}\n{}\n", Pubkey::default());
+
+        let message = errors(validate(parse_lines(&content)));
+
+        assert!(message.starts_with("1 invalid identities"));
+        assert!(message.contains(
+            "line 2: invalid identity 11111111111111111111111111111111 (the default public key)"
+        ));
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let [a, b] = keys(2)[..] else { unreachable!() };
+        let content = format!("[\"{a}\", \"{b}\"]");
+
+        assert_eq!(validate(parse_json(&content).unwrap()).unwrap(), vec![a, b]);
+
+        let content = format!("[\"{a}\", \"{a}\"]");
+        assert!(
+            errors(validate(parse_json(&content).unwrap())).contains("entry 2: duplicate identity")
+        );
+
+        assert!(parse_json("{\"identities\": []}").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let [a, b] = keys(2)[..] else { unreachable!() };
+        let content =
+            format!("name,identity\n\"Validator, Inc.\",{a}\n\"The \"\"Best\"\" One\",\"{b}\"\n");
+
+        assert_eq!(
+            validate(parse_csv(&content, Some("identity")).unwrap()).unwrap(),
+            vec![a, b]
+        );
+        assert!(errors(validate(parse_csv(&content, None).unwrap()))
+            .contains("line 2: invalid public key \"Validator, Inc.\""));
+        assert!(parse_csv(&content, Some("vote")).is_err());
+
+        let content = format!("identity,name\n{a},first\n\n{b}\n");
+        assert_eq!(
+            validate(parse_csv(&content, None).unwrap()).unwrap(),
+            vec![a, b]
+        );
+
+        let message = parse_csv(&content, Some("name")).unwrap_err().to_string();
+        assert_eq!(message, "line 4: missing column 2");
+    }
+
+    #[test]
+    fn test_split_csv_record() {
+        assert_eq!(
+            split_csv_record(r#" a ,"b, c","d ""e""","#),
+            vec!["a", "b, c", "d \"e\"", ""]
+        );
+    }
+
+    #[test]
+    fn test_resolve_format() {
+        let json = Path::new("identities.json");
+        let csv = Path::new("identities.csv");
+        let txt = Path::new("identities.txt");
+
+        assert_eq!(InputFormat::Auto.resolve(json, ""), InputFormat::Json);
+        assert_eq!(InputFormat::Auto.resolve(csv, ""), InputFormat::Csv);
+        assert_eq!(InputFormat::Auto.resolve(txt, "a\nb"), InputFormat::Lines);
+        assert_eq!(
+            InputFormat::Auto.resolve(txt, " [\"a\"]"),
+            InputFormat::Json
+        );
+        assert_eq!(InputFormat::Lines.resolve(json, "[]"), InputFormat::Lines);
+    }
+
+    #[test]
+    fn test_read_file_and_arguments() {
+        let [a, b, c] = keys(3)[..] else {
+            unreachable!()
+        };
+        let path = std::env::temp_dir().join(format!("shield-input-{a}.json"));
+        std::fs::write(&path, format!("[\"{a}\", \"{b}\"]")).unwrap();
+
+        let input = IdentitiesInput {
+            identities_path: Some(path.clone()),
+            identity: vec![c, a],
+            ..Default::default()
+        };
+        let message = errors(input.read());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(message.contains(&format!(
+            "--identity #2: duplicate identity {a} (first listed on entry 1)"
+        )));
+
+        let input = IdentitiesInput {
+            identity: vec![c, b],
+            ..Default::default()
+        };
+        assert_eq!(input.read().unwrap(), vec![c, b]);
+    }
+
+    #[test]
+    fn test_format_identities_round_trip() {
+        let identities = keys(3);
+
+        for format in [InputFormat::Lines, InputFormat::Json, InputFormat::Csv] {
+            let content = format_identities(&identities, format, None);
+            let entries = match format {
+                InputFormat::Json => parse_json(&content).unwrap(),
+                InputFormat::Csv => parse_csv(&content, Some("identity")).unwrap(),
+                _ => parse_lines(&content),
+            };
+
+            assert_eq!(validate(entries).unwrap(), identities);
+        }
+    }
+
+    #[test]
+    fn test_stdin_requires_confirmation_flag() {
+        let input = IdentitiesInput {
+            identities_path: Some(PathBuf::from("-")),
+            ..Default::default()
+        };
+
+        // Fails before reading stdin since nothing would be left to answer the prompt.
+        assert!(errors(input.read_with_prompt(true)).contains("pass --yes"));
+
+        let input = IdentitiesInput {
+            identity: vec![Pubkey::new_unique()],
+            ..Default::default()
+        };
+        assert!(input.read_with_prompt(true).is_ok());
+    }
+}
@@ -1,26 +1,31 @@
 mod command;
+mod input;
+mod output;
+mod profile;
 
 use anyhow::{Context, Result};
 use bs58::decode;
-use clap_derive::{Parser as DeriveParser, Subcommand};
-use log::info;
+use clap_derive::{Args as DeriveArgs, Parser as DeriveParser, Subcommand};
 use serde_json::from_str as parse_json_str;
 use solana_cli_config::Config;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_commitment_config::{CommitmentConfig, ParseCommitmentLevelError};
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
-use spl_token_metadata_interface::state::TokenMetadata;
-use std::fmt;
+use solana_signer::Signer;
 use std::fs::read_to_string as read_path;
+use std::io::{read_to_string, stdin};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::{str::FromStr, time::Duration};
-use yellowstone_shield_client::types::PermissionStrategy;
+use yellowstone_shield_client::{
+    types::PermissionStrategy, PolicyMetadata, MAX_COMPUTE_UNIT_LIMIT,
+};
 
 pub use command::*;
-
-use crate::command::policy::PolicyVersion;
+pub use input::*;
+pub use output::*;
+pub use profile::*;
 
 #[derive(Debug, DeriveParser)]
 #[command(
@@ -42,10 +47,111 @@ pub struct Args {
     #[arg(short, long, global = true)]
     pub keypair: Option<String>,
 
+    /// Path to the Solana CLI config file, defaults to the Solana CLI's own config
+    #[arg(short = 'C', long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Shield profile to use, defaults to the `default` entry of the profiles file
+    #[arg(short, long, global = true)]
+    pub profile: Option<String>,
+
+    /// Path to the Shield profiles file [default: ~/.config/shield/profiles.toml]
+    #[arg(long, global = true)]
+    pub profiles_path: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub options: RunOptions,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
+/// Options shared by every command.
+#[derive(Debug, Clone, DeriveArgs)]
+pub struct RunOptions {
+    /// Format of the results written to stdout
+    #[arg(short, long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Print unsigned base64 transactions instead of signing and sending them
+    #[arg(long, global = true)]
+    pub unsigned: bool,
+
+    /// Owner of the policy tokens when the transactions are signed externally, defaults to the
+    /// keypair's public key
+    #[arg(long, global = true, requires = "unsigned")]
+    pub owner: Option<Pubkey>,
+
+    /// Maximum number of batches of a step sent concurrently
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_IN_FLIGHT)]
+    pub max_in_flight: usize,
+
+    /// Priority fee paid per compute unit, in micro-lamports
+    #[arg(long, global = true)]
+    pub priority_fee: Option<u64>,
+
+    /// Compute units requested by every transaction, estimated by simulation when a priority
+    /// fee is set
+    #[arg(
+        long,
+        global = true,
+        value_parser = clap::value_parser!(u32).range(1..=MAX_COMPUTE_UNIT_LIMIT as i64)
+    )]
+    pub compute_unit_limit: Option<u32>,
+}
+
+/// Fields describing a policy, stored in the `additional_metadata` of its token.
+#[derive(Debug, Clone, Default, DeriveArgs)]
+pub struct MetadataArgs {
+    /// What the policy is used for
+    #[arg(long)]
+    pub description: Option<String>,
+
+    /// Kind of identities listed by the policy, e.g. `sandwich`
+    #[arg(long)]
+    pub category: Option<String>,
+
+    /// How to reach the maintainer of the policy
+    #[arg(long)]
+    pub contact: Option<String>,
+
+    /// How often the identities are updated, e.g. `daily`
+    #[arg(long)]
+    pub update_cadence: Option<String>,
+
+    /// Where the identities are sourced from
+    #[arg(long)]
+    pub source_url: Option<String>,
+}
+
+impl MetadataArgs {
+    /// The fields that are set, as `additional_metadata` entries.
+    pub fn fields(&self) -> Vec<(String, String)> {
+        PolicyMetadata {
+            description: self.description.clone(),
+            category: self.category.clone(),
+            contact: self.contact.clone(),
+            update_cadence: self.update_cadence.clone(),
+            source_url: self.source_url.clone(),
+            ..Default::default()
+        }
+        .fields()
+    }
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            output: OutputFormat::default(),
+            unsigned: false,
+            owner: None,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            priority_fee: None,
+            compute_unit_limit: None,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Manage policies
@@ -58,6 +164,12 @@ pub enum Command {
         #[command(subcommand)]
         action: IdentitiesAction,
     },
+    /// Broadcast transactions signed outside of the CLI
+    Submit {
+        /// File with one base64 transaction per line, or `-` to read from stdin
+        #[arg(long)]
+        transactions_path: PathBuf,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -79,6 +191,13 @@ pub enum PolicyAction {
         /// The URI of the policy
         #[arg(long)]
         uri: String,
+
+        #[command(flatten)]
+        metadata: MetadataArgs,
+
+        /// Token balance required to mutate the policy, any positive balance by default
+        #[arg(long)]
+        min_balance: Option<u64>,
     },
     /// Delete a policy
     Delete {
@@ -92,6 +211,99 @@ pub enum PolicyAction {
         #[arg(long)]
         mint: Pubkey,
     },
+    /// List all policies of the program
+    List {
+        /// Only list policies whose mint is held by the configured keypair
+        #[arg(long)]
+        owned: bool,
+        /// Only list policies not yet migrated to the latest version
+        #[arg(long)]
+        legacy: bool,
+    },
+    /// Migrate a legacy policy to the latest version
+    Migrate {
+        /// The mint address associated with the policy
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Update the token metadata of a policy
+    UpdateMetadata {
+        /// The mint address associated with the policy
+        #[arg(long)]
+        mint: Pubkey,
+
+        /// The new name of the policy
+        #[arg(long)]
+        name: Option<String>,
+
+        /// The new symbol of the policy
+        #[arg(long)]
+        symbol: Option<String>,
+
+        /// The new URI of the policy
+        #[arg(long)]
+        uri: Option<String>,
+
+        #[command(flatten)]
+        metadata: MetadataArgs,
+
+        /// An additional metadata field to add or overwrite, can be repeated
+        #[arg(long = "field", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        fields: Vec<(String, String)>,
+    },
+    /// Set the token balance required to mutate a policy
+    SetMinBalance {
+        /// The mint address associated with the policy
+        #[arg(long)]
+        mint: Pubkey,
+
+        /// Number of tokens required, `0` for any positive balance
+        #[arg(long, conflicts_with = "percent", required_unless_present = "percent")]
+        amount: Option<u64>,
+
+        /// Share of the token supply required, in percent
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=100))]
+        percent: Option<u8>,
+    },
+    /// Lock a policy, its identities can no longer be changed and it can no longer be deleted
+    Lock {
+        /// The mint address associated with the policy
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Transfer the policy token to a new owner
+    Transfer {
+        /// The mint address associated with the policy
+        #[arg(long)]
+        mint: Pubkey,
+
+        /// The new owner of the policy
+        #[arg(long)]
+        to: Pubkey,
+
+        /// Remove the mint authority so no more policy tokens can be minted
+        #[arg(long)]
+        revoke_mint_authority: bool,
+    },
+    /// Copy the identities of a policy into another policy
+    Clone {
+        /// The mint address of the policy to copy the identities from
+        #[arg(long)]
+        from_mint: Pubkey,
+        /// RPC URL of the cluster holding the source policy, defaults to the configured RPC
+        #[arg(long)]
+        from_url: Option<String>,
+        /// The mint address of the policy to update
+        #[arg(long)]
+        to_mint: Pubkey,
+
+        /// Print the changes without sending any transaction
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+        /// Send the transactions without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -102,8 +314,15 @@ pub enum IdentitiesAction {
         #[arg(long)]
         mint: Pubkey,
         /// The identities to add to the policy
+        #[command(flatten)]
+        input: IdentitiesInput,
+
+        /// Print the changes without sending any transaction
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+        /// Send the transactions without asking for confirmation
         #[arg(long)]
-        identities_path: PathBuf,
+        yes: bool,
     },
     /// Update/Replace Identities for a Policy
     Update {
@@ -111,8 +330,15 @@ pub enum IdentitiesAction {
         #[arg(long)]
         mint: Pubkey,
         /// The identities to update/replace
+        #[command(flatten)]
+        input: IdentitiesInput,
+
+        /// Print the changes without sending any transaction
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+        /// Send the transactions without asking for confirmation
         #[arg(long)]
-        identities_path: PathBuf,
+        yes: bool,
     },
 
     /// Remove identities from a policy
@@ -121,8 +347,28 @@ pub enum IdentitiesAction {
         #[arg(long)]
         mint: Pubkey,
         /// The identities to remove from the policy
+        #[command(flatten)]
+        input: IdentitiesInput,
+
+        /// Print the changes without sending any transaction
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+        /// Send the transactions without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Export the identities of a policy
+    Export {
+        /// The mint address associated with the policy
+        #[arg(long)]
+        mint: Pubkey,
+        /// File the identities are written to, stdout when omitted
         #[arg(long)]
-        identities_path: PathBuf,
+        out: Option<PathBuf>,
+        /// Format of the exported identities
+        #[arg(long, value_enum, default_value_t = InputFormat::Auto)]
+        format: InputFormat,
     },
 }
 
@@ -138,16 +384,35 @@ pub enum CliError {
     ParseCommitmentLevelError(#[from] ParseCommitmentLevelError),
     #[error("unable to parse keypair")]
     Keypair,
+    #[error("invalid profiles file: {0}")]
+    Profiles(String),
+    #[error("profile {0} not found")]
+    ProfileNotFound(String),
 }
 
-pub async fn run(config: Arc<Config>, command: Command) -> RunResult {
+pub async fn run(config: Arc<Config>, command: Command, options: RunOptions) -> RunResult {
     let client = RpcClient::new_with_timeout_and_commitment(
         config.json_rpc_url.clone(),
         Duration::from_secs(90),
         CommitmentConfig::from_str(&config.commitment).map_err::<CliError, _>(Into::into)?,
     );
-    let keypair = parse_keypair(&config.keypair_path)?;
-    let context = command::CommandContext { keypair, client };
+    let authority = match (&command, options.unsigned, options.owner) {
+        // Submitted transactions carry their own signatures, no keypair is needed.
+        (Command::Submit { .. }, _, owner) => Authority::Offline(owner.unwrap_or_default()),
+        (_, true, Some(owner)) => Authority::Offline(owner),
+        (_, true, None) => Authority::Offline(parse_keypair(&config.keypair_path)?.pubkey()),
+        (_, false, _) => Authority::Keypair(parse_keypair(&config.keypair_path)?),
+    };
+    let context = command::CommandContext {
+        authority,
+        client,
+        output: options.output,
+        send_config: SendConfig {
+            max_in_flight: options.max_in_flight.max(1),
+            compute_unit_price: options.priority_fee,
+            compute_unit_limit: options.compute_unit_limit,
+        },
+    };
 
     match &command {
         Command::Policy { action } => match action {
@@ -156,12 +421,16 @@ pub async fn run(config: Arc<Config>, command: Command) -> RunResult {
                 name,
                 symbol,
                 uri,
+                metadata,
+                min_balance,
             } => {
                 policy::CreateCommandBuilder::new()
                     .strategy(*strategy)
                     .name(name.clone())
                     .symbol(symbol.clone())
                     .uri(uri.clone())
+                    .fields(metadata.fields())
+                    .min_balance(*min_balance)
                     .run(context)
                     .await
             }
@@ -177,54 +446,172 @@ pub async fn run(config: Arc<Config>, command: Command) -> RunResult {
                     .run(context)
                     .await
             }
+            PolicyAction::List { owned, legacy } => {
+                policy::ListCommandBuilder::new()
+                    .owned(*owned)
+                    .legacy(*legacy)
+                    .run(context)
+                    .await
+            }
+            PolicyAction::Migrate { mint } => {
+                policy::MigrateCommandBuilder::new()
+                    .mint(mint)
+                    .run(context)
+                    .await
+            }
+            PolicyAction::UpdateMetadata {
+                mint,
+                name,
+                symbol,
+                uri,
+                metadata,
+                fields,
+            } => {
+                policy::UpdateMetadataCommandBuilder::new()
+                    .mint(mint)
+                    .name(name.clone())
+                    .symbol(symbol.clone())
+                    .uri(uri.clone())
+                    .fields(
+                        metadata
+                            .fields()
+                            .into_iter()
+                            .chain(fields.clone())
+                            .collect(),
+                    )
+                    .run(context)
+                    .await
+            }
+            PolicyAction::SetMinBalance {
+                mint,
+                amount,
+                percent,
+            } => {
+                let min_balance = match (amount, percent) {
+                    (Some(amount), _) => policy::MinBalance::Amount(*amount),
+                    (None, Some(percent)) => policy::MinBalance::Percent(*percent),
+                    (None, None) => unreachable!("clap requires --amount or --percent"),
+                };
+
+                policy::SetMinBalanceCommandBuilder::new()
+                    .mint(mint)
+                    .min_balance(min_balance)
+                    .run(context)
+                    .await
+            }
+            PolicyAction::Lock { mint } => {
+                policy::LockCommandBuilder::new()
+                    .mint(mint)
+                    .run(context)
+                    .await
+            }
+            PolicyAction::Transfer {
+                mint,
+                to,
+                revoke_mint_authority,
+            } => {
+                policy::TransferCommandBuilder::new()
+                    .mint(mint)
+                    .to(to)
+                    .revoke_mint_authority(*revoke_mint_authority)
+                    .run(context)
+                    .await
+            }
+            PolicyAction::Clone {
+                from_mint,
+                from_url,
+                to_mint,
+                dry_run,
+                yes,
+            } => {
+                policy::CloneCommandBuilder::new()
+                    .from_mint(from_mint)
+                    .from_url(from_url.as_deref())
+                    .to_mint(to_mint)
+                    .dry_run(*dry_run)
+                    .yes(*yes)
+                    .run(context)
+                    .await
+            }
         },
         Command::Identities { action } => match action {
             IdentitiesAction::Add {
                 mint,
-                identities_path,
+                input,
+                dry_run,
+                yes,
             } => {
-                let identities: Vec<Pubkey> = read_path(identities_path)?
-                    .lines()
-                    .filter_map(|s| Pubkey::from_str(s.trim()).ok())
-                    .collect();
+                let identities = input.read_with_prompt(!*dry_run && !*yes)?;
 
                 identity::AddBatchCommandBuilder::new()
                     .mint(mint)
                     .identities(identities)
+                    .dry_run(*dry_run)
+                    .yes(*yes)
                     .run(context)
                     .await
             }
             IdentitiesAction::Update {
                 mint,
-                identities_path,
+                input,
+                dry_run,
+                yes,
             } => {
-                let identities: Vec<Pubkey> = read_path(identities_path)?
-                    .lines()
-                    .filter_map(|s| Pubkey::from_str(s.trim()).ok())
-                    .collect();
+                let identities = input.read_with_prompt(!*dry_run && !*yes)?;
 
                 identity::UpdateBatchCommandBuilder::new()
                     .mint(mint)
                     .identities(identities)
+                    .dry_run(*dry_run)
+                    .yes(*yes)
                     .run(context)
                     .await
             }
             IdentitiesAction::Remove {
                 mint,
-                identities_path,
+                input,
+                dry_run,
+                yes,
             } => {
-                let identities: Vec<Pubkey> = read_path(identities_path)?
-                    .lines()
-                    .filter_map(|s| Pubkey::from_str(s.trim()).ok())
-                    .collect();
+                let identities = input.read_with_prompt(!*dry_run && !*yes)?;
 
                 identity::RemoveBatchCommandBuilder::new()
                     .mint(mint)
                     .identities(identities)
+                    .dry_run(*dry_run)
+                    .yes(*yes)
+                    .run(context)
+                    .await
+            }
+            IdentitiesAction::Export { mint, out, format } => {
+                identity::ExportCommandBuilder::new()
+                    .mint(mint)
+                    .out(out.clone())
+                    .format(*format)
                     .run(context)
                     .await
             }
         },
+        Command::Submit { transactions_path } => {
+            let transactions = if transactions_path.as_os_str() == "-" {
+                read_to_string(stdin())?
+            } else {
+                read_path(transactions_path)?
+            };
+            let transactions = transactions
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    submit::decode_transaction(line).with_context(|| format!("line {}", i + 1))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            submit::SubmitCommandBuilder::new()
+                .transactions(transactions)
+                .run(context)
+                .await
+        }
     }
 }
 
@@ -237,68 +624,10 @@ fn parse_keypair(keypair_path: &str) -> Result<Keypair, CliError> {
     Keypair::from_bytes(&secret_bytes).map_err(|_| CliError::Keypair)
 }
 
-pub struct LogPolicy<'a> {
-    token_mint: &'a Pubkey,
-    token_metadata: &'a TokenMetadata,
-    policy_address: &'a Pubkey,
-    policy_info: &'a PolicyVersion,
-    identities: Option<&'a Vec<Pubkey>>,
-}
-
-impl<'a> LogPolicy<'a> {
-    pub fn new(
-        token_mint: &'a Pubkey,
-        token_metadata: &'a TokenMetadata,
-        policy_address: &'a Pubkey,
-        policy_info: &'a PolicyVersion,
-        identities: Option<&'a Vec<Pubkey>>,
-    ) -> Self {
-        LogPolicy {
-            token_mint,
-            token_metadata,
-            policy_address,
-            policy_info,
-            identities,
-        }
-    }
-
-    fn log(&self) {
-        info!("{}", self);
-    }
-}
-
-impl fmt::Display for LogPolicy<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f)?;
-        writeln!(f)?;
-        writeln!(f, "📜 Policy")?;
-        writeln!(f, "--------------------------------")?;
-        writeln!(f, "🏠 Addresses")?;
-        writeln!(f, "  📜 Policy: {}", self.policy_address)?;
-        writeln!(f, "  🪙 Mint: {}", self.token_mint)?;
-        writeln!(f, "--------------------------------")?;
-        writeln!(f, "🔍 Details")?;
-        let strategy = match self.policy_info.strategy() {
-            0 => "❌ Strategy: Deny",
-            1 => "✅ Strategy: Allow",
-            _ => "❓ Strategy: Unknown",
-        };
-        writeln!(f, "  {}", strategy)?;
-        writeln!(f, "  🏷️  Name: {}", self.token_metadata.name)?;
-        writeln!(f, "  🔖 Symbol: {}", self.token_metadata.symbol)?;
-        writeln!(f, "  🌐 URI: {}", self.token_metadata.uri)?;
-        writeln!(f, "--------------------------------")?;
-        if let Some(identities) = self.identities {
-            writeln!(f, "  🔑 Identities in policy:")?;
-            if !identities.is_empty() {
-                for (i, identity) in identities.iter().enumerate() {
-                    writeln!(f, "    {}. {}", i, identity)?;
-                }
-            } else {
-                writeln!(f, "    []")?;
-            }
-            writeln!(f, "--------------------------------")?;
-        }
-        Ok(())
+/// Parses a `KEY=VALUE` metadata field, the value may contain `=`.
+fn parse_key_value(field: &str) -> Result<(String, String), String> {
+    match field.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("invalid field `{}`, expected KEY=VALUE", field)),
     }
 }
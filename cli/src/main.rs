@@ -1,23 +1,45 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Result;
 use clap::Parser;
 use solana_cli_config::{Config, CONFIG_FILE};
-use yellowstone_shield_cli::{run, Args, CliError};
+use yellowstone_shield_cli::{run, Args, CliError, Profiles};
 
 #[tokio::main]
 async fn main() -> Result<(), CliError> {
     let args = Args::parse();
 
+    // Logs go to stderr and never mix with the results printed to stdout.
     env_logger::builder()
-        .filter_level(log::LevelFilter::Info)
+        .filter_level(args.log_level.parse().unwrap_or(log::LevelFilter::Off))
         .parse_default_env()
         .init();
 
-    let config_file = CONFIG_FILE.as_ref().ok_or(CliError::ConfigFilePathError)?;
-
-    let mut config = Config::load(config_file).map_err::<CliError, _>(Into::into)?;
+    let mut config = match &args.config {
+        Some(config_file) => Config::load(&config_file.to_string_lossy())?,
+        None => {
+            let config_file = CONFIG_FILE.as_ref().ok_or(CliError::ConfigFilePathError)?;
+
+            // Fall back to the Solana CLI defaults when it was never configured.
+            if PathBuf::from(config_file).exists() {
+                Config::load(config_file)?
+            } else {
+                Config::default()
+            }
+        }
+    };
+
+    let profiles = match (args.profiles_path, Profiles::default_path()) {
+        (Some(path), _) => Profiles::load(&path)?,
+        (None, Some(path)) if path.exists() => Profiles::load(&path)?,
+        _ => Profiles::default(),
+    };
+
+    if let Some(profile) = profiles.select(args.profile.as_deref())? {
+        profile.apply(&mut config);
+    }
 
+    // Overrides only apply to this invocation, the config file is never written.
     if let Some(custom_json_rpc_url) = args.rpc {
         config.json_rpc_url = custom_json_rpc_url;
     }
@@ -26,13 +48,9 @@ async fn main() -> Result<(), CliError> {
         config.keypair_path = custom_keypair_path;
     }
 
-    config
-        .save(config_file)
-        .map_err::<CliError, _>(Into::into)?;
-
     let config = Arc::new(config);
 
-    run(config, args.command).await?;
+    run(config, args.command, args.options).await?;
 
     Ok(())
 }
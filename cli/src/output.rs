@@ -0,0 +1,672 @@
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use clap_derive::ValueEnum;
+use serde_json::{json, Value};
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use spl_token_metadata_interface::state::TokenMetadata;
+use yellowstone_shield_client::{types::PermissionStrategy, PolicyMetadata};
+
+use crate::{
+    identity::IdentityPlan,
+    policy::{PolicySummary, PolicyVersion},
+    submit::encode_transaction,
+    BatchResult, UnsignedTransaction,
+};
+
+/// Format of the results written to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// A single JSON document
+    Json,
+    /// A header row followed by comma separated records
+    Csv,
+}
+
+/// A command result that can be written to stdout in every `OutputFormat`.
+///
+/// Results are printed independently of the logger so they can be consumed by scripts.
+pub trait CommandOutput: fmt::Display {
+    fn to_json(&self) -> Value;
+
+    fn csv_header(&self) -> &'static [&'static str];
+
+    fn csv_records(&self) -> Vec<Vec<String>>;
+
+    fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => print!("{}", self),
+            OutputFormat::Json => println!("{:#}", self.to_json()),
+            OutputFormat::Csv => {
+                println!("{}", self.csv_header().join(","));
+                for record in self.csv_records() {
+                    let record: Vec<_> = record.iter().map(|field| csv_field(field)).collect();
+                    println!("{}", record.join(","));
+                }
+            }
+        }
+    }
+}
+
+/// Quotes a CSV field when it contains a delimiter, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn strategy_name(strategy: u8) -> String {
+    PermissionStrategy::try_from(strategy)
+        .map(|strategy| format!("{:?}", strategy))
+        .unwrap_or_else(|_| "Unknown".to_string())
+}
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn metadata_json(metadata: &TokenMetadata) -> Value {
+    let policy_metadata = PolicyMetadata::from(metadata);
+
+    json!({
+        "name": metadata.name,
+        "symbol": metadata.symbol,
+        "uri": metadata.uri,
+        "description": policy_metadata.description,
+        "category": policy_metadata.category,
+        "contact": policy_metadata.contact,
+        "update_cadence": policy_metadata.update_cadence,
+        "source_url": policy_metadata.source_url,
+        "additional_metadata": metadata
+            .additional_metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+            .collect::<serde_json::Map<_, _>>(),
+    })
+}
+
+pub struct LogPolicy<'a> {
+    token_mint: &'a Pubkey,
+    token_metadata: &'a TokenMetadata,
+    policy_address: &'a Pubkey,
+    policy_info: &'a PolicyVersion,
+    identities: Option<&'a Vec<Pubkey>>,
+    signatures: &'a [Signature],
+    batches: &'a [BatchResult],
+}
+
+impl<'a> LogPolicy<'a> {
+    pub fn new(
+        token_mint: &'a Pubkey,
+        token_metadata: &'a TokenMetadata,
+        policy_address: &'a Pubkey,
+        policy_info: &'a PolicyVersion,
+        identities: Option<&'a Vec<Pubkey>>,
+    ) -> Self {
+        LogPolicy {
+            token_mint,
+            token_metadata,
+            policy_address,
+            policy_info,
+            identities,
+            signatures: &[],
+            batches: &[],
+        }
+    }
+
+    /// Set the signatures of the transactions sent by the command
+    pub fn signatures(mut self, signatures: &'a [Signature]) -> Self {
+        self.signatures = signatures;
+        self
+    }
+
+    /// Set the batches confirmed by an identities command
+    pub fn batches(mut self, batches: &'a [BatchResult]) -> Self {
+        self.batches = batches;
+        self
+    }
+}
+
+impl fmt::Display for LogPolicy<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "📜 Policy")?;
+        writeln!(f, "--------------------------------")?;
+        writeln!(f, "🏠 Addresses")?;
+        writeln!(f, "  📜 Policy: {}", self.policy_address)?;
+        writeln!(f, "  🪙 Mint: {}", self.token_mint)?;
+        writeln!(f, "--------------------------------")?;
+        writeln!(f, "🔍 Details")?;
+        let strategy = match self.policy_info.strategy() {
+            0 => "❌ Strategy: Deny",
+            1 => "✅ Strategy: Allow",
+            _ => "❓ Strategy: Unknown",
+        };
+        writeln!(f, "  {}", strategy)?;
+        if let Some(revision) = self.policy_info.revision() {
+            writeln!(f, "  🔢 Revision: {}", revision)?;
+        }
+        if let Some(min_balance) = self.policy_info.min_balance() {
+            writeln!(f, "  ⚖️  Min Balance: {}", min_balance)?;
+        }
+        if self.policy_info.locked() {
+            writeln!(f, "  🔒 Locked")?;
+        }
+        writeln!(f, "  🏷️  Name: {}", self.token_metadata.name)?;
+        writeln!(f, "  🔖 Symbol: {}", self.token_metadata.symbol)?;
+        writeln!(f, "  🌐 URI: {}", self.token_metadata.uri)?;
+        let policy_metadata = PolicyMetadata::from(self.token_metadata);
+        for (label, value) in [
+            ("📝 Description", &policy_metadata.description),
+            ("🗂️  Category", &policy_metadata.category),
+            ("📮 Contact", &policy_metadata.contact),
+            ("⏱️  Update cadence", &policy_metadata.update_cadence),
+            ("🔗 Source", &policy_metadata.source_url),
+        ] {
+            if let Some(value) = value {
+                writeln!(f, "  {}: {}", label, value)?;
+            }
+        }
+        writeln!(f, "--------------------------------")?;
+        if let Some(identities) = self.identities {
+            writeln!(f, "  🔑 Identities in policy:")?;
+            if !identities.is_empty() {
+                for (i, identity) in identities.iter().enumerate() {
+                    writeln!(f, "    {}. {}", i, identity)?;
+                }
+            } else {
+                writeln!(f, "    []")?;
+            }
+            writeln!(f, "--------------------------------")?;
+        }
+        if self.batches.is_empty() {
+            return fmt_signatures(f, self.signatures);
+        }
+
+        writeln!(f, "  📦 Batches:")?;
+        for batch in self.batches {
+            writeln!(
+                f,
+                "    {:?} #{}: {}",
+                batch.phase, batch.batch, batch.signature
+            )?;
+        }
+        writeln!(f, "--------------------------------")
+    }
+}
+
+impl CommandOutput for LogPolicy<'_> {
+    fn to_json(&self) -> Value {
+        json!({
+            "policy": self.policy_address.to_string(),
+            "mint": self.token_mint.to_string(),
+            "strategy": strategy_name(self.policy_info.strategy()),
+            "revision": self.policy_info.revision(),
+            "min_balance": self.policy_info.min_balance(),
+            "locked": self.policy_info.locked(),
+            "metadata": metadata_json(self.token_metadata),
+            "identities": self
+                .identities
+                .map(|identities| identities.iter().map(ToString::to_string).collect::<Vec<_>>()),
+            "signatures": self.signatures.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            "batches": self
+                .batches
+                .iter()
+                .map(|batch| {
+                    json!({
+                        "phase": batch.phase,
+                        "batch": batch.batch,
+                        "signature": batch.signature.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn csv_header(&self) -> &'static [&'static str] {
+        &[
+            "policy",
+            "mint",
+            "name",
+            "symbol",
+            "uri",
+            "description",
+            "category",
+            "contact",
+            "update_cadence",
+            "source_url",
+            "strategy",
+            "revision",
+            "min_balance",
+            "locked",
+            "identities",
+            "signatures",
+        ]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let policy_metadata = PolicyMetadata::from(self.token_metadata);
+
+        vec![vec![
+            self.policy_address.to_string(),
+            self.token_mint.to_string(),
+            self.token_metadata.name.clone(),
+            self.token_metadata.symbol.clone(),
+            self.token_metadata.uri.clone(),
+            policy_metadata.description.unwrap_or_default(),
+            policy_metadata.category.unwrap_or_default(),
+            policy_metadata.contact.unwrap_or_default(),
+            policy_metadata.update_cadence.unwrap_or_default(),
+            policy_metadata.source_url.unwrap_or_default(),
+            strategy_name(self.policy_info.strategy()),
+            self.policy_info
+                .revision()
+                .map(|revision| revision.to_string())
+                .unwrap_or_default(),
+            self.policy_info
+                .min_balance()
+                .map(|min_balance| min_balance.to_string())
+                .unwrap_or_default(),
+            self.policy_info.locked().to_string(),
+            self.identities
+                .map(|identities| join(identities))
+                .unwrap_or_default(),
+            join(self.signatures),
+        ]]
+    }
+}
+
+/// A policy closed by `policy delete`.
+pub struct LogClosedPolicy<'a> {
+    token_mint: &'a Pubkey,
+    policy_address: &'a Pubkey,
+    signatures: &'a [Signature],
+}
+
+impl<'a> LogClosedPolicy<'a> {
+    pub fn new(
+        token_mint: &'a Pubkey,
+        policy_address: &'a Pubkey,
+        signatures: &'a [Signature],
+    ) -> Self {
+        LogClosedPolicy {
+            token_mint,
+            policy_address,
+            signatures,
+        }
+    }
+}
+
+impl fmt::Display for LogClosedPolicy<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "🗑️  Policy closed")?;
+        writeln!(f, "--------------------------------")?;
+        writeln!(f, "  📜 Policy: {}", self.policy_address)?;
+        writeln!(f, "  🪙 Mint: {}", self.token_mint)?;
+        writeln!(f, "--------------------------------")?;
+        fmt_signatures(f, self.signatures)
+    }
+}
+
+impl CommandOutput for LogClosedPolicy<'_> {
+    fn to_json(&self) -> Value {
+        json!({
+            "policy": self.policy_address.to_string(),
+            "mint": self.token_mint.to_string(),
+            "signatures": self.signatures.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        })
+    }
+
+    fn csv_header(&self) -> &'static [&'static str] {
+        &["policy", "mint", "signatures"]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.policy_address.to_string(),
+            self.token_mint.to_string(),
+            join(self.signatures),
+        ]]
+    }
+}
+
+fn fmt_signatures(f: &mut fmt::Formatter, signatures: &[Signature]) -> fmt::Result {
+    if signatures.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f, "  ✍️  Transaction signatures:")?;
+    for signature in signatures {
+        writeln!(f, "    {}", signature)?;
+    }
+    writeln!(f, "--------------------------------")
+}
+
+pub struct LogPolicies<'a> {
+    policies: &'a [PolicySummary],
+}
+
+impl<'a> LogPolicies<'a> {
+    pub fn new(policies: &'a [PolicySummary]) -> Self {
+        LogPolicies { policies }
+    }
+}
+
+impl fmt::Display for LogPolicies<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "📜 Policies ({})", self.policies.len())?;
+        writeln!(
+            f,
+            "{:<44}  {:<44}  {:<24}  {:<10}  {:<8}  {:<8}  {:>10}",
+            "Policy", "Mint", "Name", "Symbol", "Strategy", "Kind", "Identities"
+        )?;
+        for policy in self.policies {
+            let mint = policy
+                .mint
+                .map(|mint| mint.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let (name, symbol) = policy
+                .metadata
+                .as_ref()
+                .map(|metadata| (metadata.name.as_str(), metadata.symbol.as_str()))
+                .unwrap_or(("-", "-"));
+            writeln!(
+                f,
+                "{:<44}  {:<44}  {:<24}  {:<10}  {:<8}  {:<8}  {:>10}",
+                policy.address,
+                mint,
+                name,
+                symbol,
+                format!("{:?}", policy.strategy),
+                format!("{:?}", policy.kind),
+                policy.identities_len
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl CommandOutput for LogPolicies<'_> {
+    fn to_json(&self) -> Value {
+        self.policies
+            .iter()
+            .map(|policy| {
+                json!({
+                    "policy": policy.address.to_string(),
+                    "mint": policy.mint.map(|mint| mint.to_string()),
+                    "strategy": format!("{:?}", policy.strategy),
+                    "metadata": policy.metadata.as_ref().map(metadata_json),
+                    "identities_len": policy.identities_len,
+                    "kind": format!("{:?}", policy.kind),
+                })
+            })
+            .collect()
+    }
+
+    fn csv_header(&self) -> &'static [&'static str] {
+        &[
+            "policy",
+            "mint",
+            "name",
+            "symbol",
+            "uri",
+            "strategy",
+            "identities_len",
+            "kind",
+        ]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        self.policies
+            .iter()
+            .map(|policy| {
+                let metadata = policy.metadata.as_ref();
+                vec![
+                    policy.address.to_string(),
+                    policy.mint.map(|mint| mint.to_string()).unwrap_or_default(),
+                    metadata.map(|m| m.name.clone()).unwrap_or_default(),
+                    metadata.map(|m| m.symbol.clone()).unwrap_or_default(),
+                    metadata.map(|m| m.uri.clone()).unwrap_or_default(),
+                    format!("{:?}", policy.strategy),
+                    policy.identities_len.to_string(),
+                    format!("{:?}", policy.kind),
+                ]
+            })
+            .collect()
+    }
+}
+
+pub struct LogPlan<'a> {
+    plan: &'a IdentityPlan,
+}
+
+impl<'a> LogPlan<'a> {
+    pub fn new(plan: &'a IdentityPlan) -> Self {
+        LogPlan { plan }
+    }
+}
+
+impl fmt::Display for LogPlan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "📝 Plan")?;
+        writeln!(f, "--------------------------------")?;
+        writeln!(f, "  📜 Policy: {}", self.plan.policy)?;
+        if let Some(revision) = self.plan.revision {
+            writeln!(f, "  🔢 Revision: {}", revision)?;
+        }
+        writeln!(f, "--------------------------------")?;
+        writeln!(f, "  ➖ Remove ({}):", self.plan.remove.len())?;
+        for identity in &self.plan.remove {
+            writeln!(f, "    {}", identity)?;
+        }
+        writeln!(f, "  🔁 Replace ({}):", self.plan.replace.len())?;
+        for (old, identity) in &self.plan.replace {
+            if *old == Pubkey::default() {
+                writeln!(f, "    (empty slot) → {}", identity)?;
+            } else {
+                writeln!(f, "    {} → {}", old, identity)?;
+            }
+        }
+        writeln!(f, "  ➕ Add ({}):", self.plan.add.len())?;
+        for identity in &self.plan.add {
+            writeln!(f, "    {}", identity)?;
+        }
+        writeln!(f, "--------------------------------")?;
+        writeln!(f, "  📦 Transactions: {}", self.plan.transactions())?;
+        writeln!(f, "  💰 Rent delta: {} lamports", self.plan.rent_delta)?;
+        writeln!(f, "--------------------------------")
+    }
+}
+
+impl CommandOutput for LogPlan<'_> {
+    fn to_json(&self) -> Value {
+        json!({
+            "policy": self.plan.policy.to_string(),
+            "revision": self.plan.revision,
+            "remove": self.plan.remove.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            "replace": self
+                .plan
+                .replace
+                .iter()
+                .map(|(old, identity)| {
+                    json!({
+                        "old": (*old != Pubkey::default()).then(|| old.to_string()),
+                        "identity": identity.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+            "add": self.plan.add.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            "transactions": self.plan.transactions(),
+            "rent_delta": self.plan.rent_delta,
+        })
+    }
+
+    fn csv_header(&self) -> &'static [&'static str] {
+        &["policy", "action", "old", "identity"]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        let policy = self.plan.policy.to_string();
+        let removes = self.plan.remove.iter().map(|identity| {
+            vec![
+                policy.clone(),
+                "remove".to_string(),
+                identity.to_string(),
+                String::new(),
+            ]
+        });
+        let replaces = self.plan.replace.iter().map(|(old, identity)| {
+            vec![
+                policy.clone(),
+                "replace".to_string(),
+                if *old == Pubkey::default() {
+                    String::new()
+                } else {
+                    old.to_string()
+                },
+                identity.to_string(),
+            ]
+        });
+        let adds = self.plan.add.iter().map(|identity| {
+            vec![
+                policy.clone(),
+                "add".to_string(),
+                String::new(),
+                identity.to_string(),
+            ]
+        });
+
+        removes.chain(replaces).chain(adds).collect()
+    }
+}
+
+fn instruction_json(instruction: &Instruction) -> Value {
+    json!({
+        "program_id": instruction.program_id.to_string(),
+        "accounts": instruction
+            .accounts
+            .iter()
+            .map(|account| {
+                json!({
+                    "pubkey": account.pubkey.to_string(),
+                    "is_signer": account.is_signer,
+                    "is_writable": account.is_writable,
+                })
+            })
+            .collect::<Vec<_>>(),
+        "data": BASE64.encode(&instruction.data),
+    })
+}
+
+/// Transactions printed for an offline authority to sign.
+///
+/// The text format prints one base64 transaction per line, the input expected by `submit`.
+pub struct LogUnsignedTransactions<'a> {
+    transactions: &'a [UnsignedTransaction],
+}
+
+impl<'a> LogUnsignedTransactions<'a> {
+    pub fn new(transactions: &'a [UnsignedTransaction]) -> Self {
+        LogUnsignedTransactions { transactions }
+    }
+}
+
+impl fmt::Display for LogUnsignedTransactions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for unsigned in self.transactions {
+            writeln!(f, "{}", encode_transaction(&unsigned.transaction))?;
+        }
+        Ok(())
+    }
+}
+
+impl CommandOutput for LogUnsignedTransactions<'_> {
+    fn to_json(&self) -> Value {
+        self.transactions
+            .iter()
+            .map(|unsigned| {
+                let message = &unsigned.transaction.message;
+                let missing_signers: Vec<_> = message
+                    .account_keys
+                    .iter()
+                    .zip(&unsigned.transaction.signatures)
+                    .filter(|(_, signature)| **signature == Signature::default())
+                    .map(|(signer, _)| signer.to_string())
+                    .collect();
+
+                json!({
+                    "transaction": encode_transaction(&unsigned.transaction),
+                    "missing_signers": missing_signers,
+                    "recent_blockhash": message.recent_blockhash.to_string(),
+                    "instructions": unsigned
+                        .instructions
+                        .iter()
+                        .map(instruction_json)
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect()
+    }
+
+    fn csv_header(&self) -> &'static [&'static str] {
+        &["batch", "transaction"]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .map(|(batch, unsigned)| {
+                vec![batch.to_string(), encode_transaction(&unsigned.transaction)]
+            })
+            .collect()
+    }
+}
+
+/// Signatures of the transactions broadcast by `submit`.
+pub struct LogSignatures<'a> {
+    signatures: &'a [Signature],
+}
+
+impl<'a> LogSignatures<'a> {
+    pub fn new(signatures: &'a [Signature]) -> Self {
+        LogSignatures { signatures }
+    }
+}
+
+impl fmt::Display for LogSignatures<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "📤 Submitted ({})", self.signatures.len())?;
+        writeln!(f, "--------------------------------")?;
+        fmt_signatures(f, self.signatures)
+    }
+}
+
+impl CommandOutput for LogSignatures<'_> {
+    fn to_json(&self) -> Value {
+        json!({
+            "signatures": self.signatures.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        })
+    }
+
+    fn csv_header(&self) -> &'static [&'static str] {
+        &["signature"]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        self.signatures
+            .iter()
+            .map(|signature| vec![signature.to_string()])
+            .collect()
+    }
+}
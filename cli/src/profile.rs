@@ -0,0 +1,87 @@
+use std::{collections::HashMap, fs::read_to_string, path::PathBuf};
+
+use serde::Deserialize;
+use solana_cli_config::Config;
+
+use crate::CliError;
+
+/// Shield CLI profiles, read from a TOML file such as:
+///
+/// ```toml
+/// default = "devnet"
+///
+/// [profiles.mainnet]
+/// rpc = "https://api.mainnet-beta.solana.com"
+/// keypair = "/home/user/.config/solana/shield-owner.json"
+///
+/// [profiles.devnet]
+/// rpc = "https://api.devnet.solana.com"
+/// commitment = "finalized"
+///
+/// [profiles.local]
+/// rpc = "http://127.0.0.1:8899"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profiles {
+    /// Profile used when none is selected on the command line.
+    pub default: Option<String>,
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+/// A named environment overriding the Solana CLI config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub rpc: Option<String>,
+    pub keypair: Option<String>,
+    pub commitment: Option<String>,
+}
+
+impl Profiles {
+    /// Default location of the profiles file, `~/.config/shield/profiles.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join(".config")
+                .join("shield")
+                .join("profiles.toml")
+        })
+    }
+
+    pub fn load(path: &PathBuf) -> Result<Self, CliError> {
+        let profiles = read_to_string(path)?;
+
+        toml::from_str(&profiles).map_err(|e| CliError::Profiles(e.to_string()))
+    }
+
+    /// Returns the profile named `name`, or the default profile when no name is given.
+    pub fn select(&self, name: Option<&str>) -> Result<Option<&Profile>, CliError> {
+        match name.or(self.default.as_deref()) {
+            Some(name) => self
+                .profiles
+                .get(name)
+                .map(Some)
+                .ok_or_else(|| CliError::ProfileNotFound(name.to_string())),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Profile {
+    /// Applies the profile on top of the Solana CLI config, in memory only.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(rpc) = &self.rpc {
+            config.json_rpc_url = rpc.clone();
+        }
+
+        if let Some(keypair) = &self.keypair {
+            config.keypair_path = keypair.clone();
+        }
+
+        if let Some(commitment) = &self.commitment {
+            config.commitment = commitment.clone();
+        }
+    }
+}
@@ -8,3 +8,4 @@
 
 export * from './policy';
 export * from './policyV2';
+export * from './policyV3';
@@ -106,6 +106,20 @@ export const SHIELD_ERROR__INVALID_STRATEGY = 0x2b; // 43
 export const SHIELD_ERROR__INVALID_POLICY_KIND = 0x2c; // 44
 /** InvalidIndexToReferenceIdentity: Invalid Index To Reference Identity */
 export const SHIELD_ERROR__INVALID_INDEX_TO_REFERENCE_IDENTITY = 0x2d; // 45
+/** IdentityAlreadyExists: Identity already exists */
+export const SHIELD_ERROR__IDENTITY_ALREADY_EXISTS = 0x2e; // 46
+/** IdentitySlotEmpty: Identity slot is empty */
+export const SHIELD_ERROR__IDENTITY_SLOT_EMPTY = 0x2f; // 47
+/** InvalidIdentity: Invalid identity */
+export const SHIELD_ERROR__INVALID_IDENTITY = 0x30; // 48
+/** RevisionMismatch: Policy revision does not match the expected revision */
+export const SHIELD_ERROR__REVISION_MISMATCH = 0x31; // 49
+/** PolicyAlreadyMigrated: Policy is already at the latest version */
+export const SHIELD_ERROR__POLICY_ALREADY_MIGRATED = 0x32; // 50
+/** InsufficientBalance: Token balance is below the minimum balance of the policy */
+export const SHIELD_ERROR__INSUFFICIENT_BALANCE = 0x33; // 51
+/** PolicyNotMigrated: Policy must be migrated to the latest version */
+export const SHIELD_ERROR__POLICY_NOT_MIGRATED = 0x34; // 52
 
 export type ShieldError =
   | typeof SHIELD_ERROR__ACCOUNT_ALREADY_INITIALIZED
@@ -123,18 +137,22 @@ export type ShieldError =
   | typeof SHIELD_ERROR__EXPECTED_SIGNER_ACCOUNT
   | typeof SHIELD_ERROR__EXPECTED_WRITABLE_ACCOUNT
   | typeof SHIELD_ERROR__GENERIC_ERROR
+  | typeof SHIELD_ERROR__IDENTITY_ALREADY_EXISTS
   | typeof SHIELD_ERROR__IDENTITY_NOT_FOUND
+  | typeof SHIELD_ERROR__IDENTITY_SLOT_EMPTY
   | typeof SHIELD_ERROR__ILLEGAL_OWNER
   | typeof SHIELD_ERROR__IMMUTABLE
   | typeof SHIELD_ERROR__INCORRECT_AUTHORITY
   | typeof SHIELD_ERROR__INCORRECT_PROGRAM_ID
   | typeof SHIELD_ERROR__INCORRECT_TOKEN_OWNER
+  | typeof SHIELD_ERROR__INSUFFICIENT_BALANCE
   | typeof SHIELD_ERROR__INSUFFICIENT_FUNDS
   | typeof SHIELD_ERROR__INVALID_ACCOUNT_DATA
   | typeof SHIELD_ERROR__INVALID_ACCOUNT_KEY
   | typeof SHIELD_ERROR__INVALID_ACCOUNT_OWNER
   | typeof SHIELD_ERROR__INVALID_ARGUMENT
   | typeof SHIELD_ERROR__INVALID_ASSOCIATED_TOKEN_ACCOUNT
+  | typeof SHIELD_ERROR__INVALID_IDENTITY
   | typeof SHIELD_ERROR__INVALID_INDEX_TO_REFERENCE_IDENTITY
   | typeof SHIELD_ERROR__INVALID_INSTRUCTION_DATA
   | typeof SHIELD_ERROR__INVALID_PDA
@@ -151,6 +169,9 @@ export type ShieldError =
   | typeof SHIELD_ERROR__MISTMATCH_MINT
   | typeof SHIELD_ERROR__NOT_ENOUGH_ACCOUNT_KEYS
   | typeof SHIELD_ERROR__NUMERICAL_OVERFLOW
+  | typeof SHIELD_ERROR__POLICY_ALREADY_MIGRATED
+  | typeof SHIELD_ERROR__POLICY_NOT_MIGRATED
+  | typeof SHIELD_ERROR__REVISION_MISMATCH
   | typeof SHIELD_ERROR__SERIALIZATION_ERROR
   | typeof SHIELD_ERROR__UNINITIALIZED_ACCOUNT
   | typeof SHIELD_ERROR__UNSUPPORTED_SYSVAR;
@@ -173,18 +194,22 @@ if (process.env.NODE_ENV !== 'production') {
     [SHIELD_ERROR__EXPECTED_SIGNER_ACCOUNT]: `Expected signer account`,
     [SHIELD_ERROR__EXPECTED_WRITABLE_ACCOUNT]: `Expected writable account`,
     [SHIELD_ERROR__GENERIC_ERROR]: `Generic program error`,
+    [SHIELD_ERROR__IDENTITY_ALREADY_EXISTS]: `Identity already exists`,
     [SHIELD_ERROR__IDENTITY_NOT_FOUND]: `identity not found`,
+    [SHIELD_ERROR__IDENTITY_SLOT_EMPTY]: `Identity slot is empty`,
     [SHIELD_ERROR__ILLEGAL_OWNER]: `Illegal owner`,
     [SHIELD_ERROR__IMMUTABLE]: `Immutable`,
     [SHIELD_ERROR__INCORRECT_AUTHORITY]: `Incorrect authority`,
     [SHIELD_ERROR__INCORRECT_PROGRAM_ID]: `Incorrect program id`,
     [SHIELD_ERROR__INCORRECT_TOKEN_OWNER]: `Incorrect token owner`,
+    [SHIELD_ERROR__INSUFFICIENT_BALANCE]: `Token balance is below the minimum balance of the policy`,
     [SHIELD_ERROR__INSUFFICIENT_FUNDS]: `Insufficient funds`,
     [SHIELD_ERROR__INVALID_ACCOUNT_DATA]: `invalid account data`,
     [SHIELD_ERROR__INVALID_ACCOUNT_KEY]: `Invalid account key`,
     [SHIELD_ERROR__INVALID_ACCOUNT_OWNER]: `Invalid account owner`,
     [SHIELD_ERROR__INVALID_ARGUMENT]: `Invalid argument`,
     [SHIELD_ERROR__INVALID_ASSOCIATED_TOKEN_ACCOUNT]: `Invalid associated token account`,
+    [SHIELD_ERROR__INVALID_IDENTITY]: `Invalid identity`,
     [SHIELD_ERROR__INVALID_INDEX_TO_REFERENCE_IDENTITY]: `Invalid Index To Reference Identity`,
     [SHIELD_ERROR__INVALID_INSTRUCTION_DATA]: `Invalid instruction data`,
     [SHIELD_ERROR__INVALID_PDA]: `Invalid PDA derivation`,
@@ -201,6 +226,9 @@ if (process.env.NODE_ENV !== 'production') {
     [SHIELD_ERROR__MISTMATCH_MINT]: `Mismatching mint`,
     [SHIELD_ERROR__NOT_ENOUGH_ACCOUNT_KEYS]: `Not enough account keys`,
     [SHIELD_ERROR__NUMERICAL_OVERFLOW]: `Numerical overflow`,
+    [SHIELD_ERROR__POLICY_ALREADY_MIGRATED]: `Policy is already at the latest version`,
+    [SHIELD_ERROR__POLICY_NOT_MIGRATED]: `Policy must be migrated to the latest version`,
+    [SHIELD_ERROR__REVISION_MISMATCH]: `Policy revision does not match the expected revision`,
     [SHIELD_ERROR__SERIALIZATION_ERROR]: `Error serializing an account`,
     [SHIELD_ERROR__UNINITIALIZED_ACCOUNT]: `Uninitialized account`,
     [SHIELD_ERROR__UNSUPPORTED_SYSVAR]: `Unsupported sysvar`,
@@ -11,10 +11,15 @@ import {
   combineCodec,
   getAddressDecoder,
   getAddressEncoder,
+  getOptionDecoder,
+  getOptionEncoder,
   getStructDecoder,
   getStructEncoder,
+  getU64Decoder,
+  getU64Encoder,
   getU8Decoder,
   getU8Encoder,
+  none,
   transformEncoder,
   type Address,
   type Codec,
@@ -25,6 +30,8 @@ import {
   type IInstruction,
   type IInstructionWithAccounts,
   type IInstructionWithData,
+  type Option,
+  type OptionOrNullable,
   type ReadonlyAccount,
   type TransactionSigner,
   type WritableAccount,
@@ -89,17 +96,26 @@ export type AddIdentityInstruction<
 export type AddIdentityInstructionData = {
   discriminator: number;
   identity: Address;
+  expectedRevision: Option<bigint>;
 };
 
-export type AddIdentityInstructionDataArgs = { identity: Address };
+export type AddIdentityInstructionDataArgs = {
+  identity: Address;
+  expectedRevision?: OptionOrNullable<number | bigint>;
+};
 
 export function getAddIdentityInstructionDataEncoder(): Encoder<AddIdentityInstructionDataArgs> {
   return transformEncoder(
     getStructEncoder([
       ['discriminator', getU8Encoder()],
       ['identity', getAddressEncoder()],
+      ['expectedRevision', getOptionEncoder(getU64Encoder())],
     ]),
-    (value) => ({ ...value, discriminator: ADD_IDENTITY_DISCRIMINATOR })
+    (value) => ({
+      ...value,
+      discriminator: ADD_IDENTITY_DISCRIMINATOR,
+      expectedRevision: value.expectedRevision ?? none(),
+    })
   );
 }
 
@@ -107,6 +123,7 @@ export function getAddIdentityInstructionDataDecoder(): Decoder<AddIdentityInstr
   return getStructDecoder([
     ['discriminator', getU8Decoder()],
     ['identity', getAddressDecoder()],
+    ['expectedRevision', getOptionDecoder(getU64Decoder())],
   ]);
 }
 
@@ -141,6 +158,7 @@ export type AddIdentityAsyncInput<
   /** The system program */
   systemProgram?: Address<TAccountSystemProgram>;
   identity: AddIdentityInstructionDataArgs['identity'];
+  expectedRevision?: AddIdentityInstructionDataArgs['expectedRevision'];
 };
 
 export async function getAddIdentityInstructionAsync<
@@ -261,6 +279,7 @@ export type AddIdentityInput<
   /** The system program */
   systemProgram?: Address<TAccountSystemProgram>;
   identity: AddIdentityInstructionDataArgs['identity'];
+  expectedRevision?: AddIdentityInstructionDataArgs['expectedRevision'];
 };
 
 export function getAddIdentityInstruction<
@@ -9,10 +9,15 @@
 import {
   BASE_ACCOUNT_SIZE,
   combineCodec,
+  getOptionDecoder,
+  getOptionEncoder,
   getStructDecoder,
   getStructEncoder,
+  getU64Decoder,
+  getU64Encoder,
   getU8Decoder,
   getU8Encoder,
+  none,
   transformEncoder,
   type Address,
   type Codec,
@@ -23,6 +28,8 @@ import {
   type IInstruction,
   type IInstructionWithAccounts,
   type IInstructionWithData,
+  type Option,
+  type OptionOrNullable,
   type ReadonlyAccount,
   type TransactionSigner,
   type WritableAccount,
@@ -84,19 +91,34 @@ export type ClosePolicyInstruction<
     ]
   >;
 
-export type ClosePolicyInstructionData = { discriminator: number };
+export type ClosePolicyInstructionData = {
+  discriminator: number;
+  expectedRevision: Option<bigint>;
+};
 
-export type ClosePolicyInstructionDataArgs = {};
+export type ClosePolicyInstructionDataArgs = {
+  expectedRevision?: OptionOrNullable<number | bigint>;
+};
 
 export function getClosePolicyInstructionDataEncoder(): Encoder<ClosePolicyInstructionDataArgs> {
   return transformEncoder(
-    getStructEncoder([['discriminator', getU8Encoder()]]),
-    (value) => ({ ...value, discriminator: CLOSE_POLICY_DISCRIMINATOR })
+    getStructEncoder([
+      ['discriminator', getU8Encoder()],
+      ['expectedRevision', getOptionEncoder(getU64Encoder())],
+    ]),
+    (value) => ({
+      ...value,
+      discriminator: CLOSE_POLICY_DISCRIMINATOR,
+      expectedRevision: value.expectedRevision ?? none(),
+    })
   );
 }
 
 export function getClosePolicyInstructionDataDecoder(): Decoder<ClosePolicyInstructionData> {
-  return getStructDecoder([['discriminator', getU8Decoder()]]);
+  return getStructDecoder([
+    ['discriminator', getU8Decoder()],
+    ['expectedRevision', getOptionDecoder(getU64Decoder())],
+  ]);
 }
 
 export function getClosePolicyInstructionDataCodec(): Codec<
@@ -129,6 +151,7 @@ export type ClosePolicyAsyncInput<
   owner?: TransactionSigner<TAccountOwner>;
   /** The system program */
   systemProgram?: Address<TAccountSystemProgram>;
+  expectedRevision?: ClosePolicyInstructionDataArgs['expectedRevision'];
 };
 
 export async function getClosePolicyInstructionAsync<
@@ -178,6 +201,9 @@ export async function getClosePolicyInstructionAsync<
     ResolvedAccount
   >;
 
+  // Original args.
+  const args = { ...input };
+
   // Resolve default values.
   if (!accounts.policy.value) {
     accounts.policy.value = await findPolicyPda({
@@ -209,7 +235,9 @@ export async function getClosePolicyInstructionAsync<
       getAccountMeta(accounts.systemProgram),
     ],
     programAddress,
-    data: getClosePolicyInstructionDataEncoder().encode({}),
+    data: getClosePolicyInstructionDataEncoder().encode(
+      args as ClosePolicyInstructionDataArgs
+    ),
   } as ClosePolicyInstruction<
     TProgramAddress,
     TAccountMint,
@@ -243,6 +271,7 @@ export type ClosePolicyInput<
   owner?: TransactionSigner<TAccountOwner>;
   /** The system program */
   systemProgram?: Address<TAccountSystemProgram>;
+  expectedRevision?: ClosePolicyInstructionDataArgs['expectedRevision'];
 };
 
 export function getClosePolicyInstruction<
@@ -290,6 +319,9 @@ export function getClosePolicyInstruction<
     ResolvedAccount
   >;
 
+  // Original args.
+  const args = { ...input };
+
   // Resolve default values.
   if (!accounts.owner.value) {
     accounts.owner.value = expectSome(accounts.payer.value);
@@ -316,7 +348,9 @@ export function getClosePolicyInstruction<
       getAccountMeta(accounts.systemProgram),
     ],
     programAddress,
-    data: getClosePolicyInstructionDataEncoder().encode({}),
+    data: getClosePolicyInstructionDataEncoder().encode(
+      args as ClosePolicyInstructionDataArgs
+    ),
   } as ClosePolicyInstruction<
     TProgramAddress,
     TAccountMint,
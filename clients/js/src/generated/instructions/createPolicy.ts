@@ -9,10 +9,15 @@
 import {
   BASE_ACCOUNT_SIZE,
   combineCodec,
+  getOptionDecoder,
+  getOptionEncoder,
   getStructDecoder,
   getStructEncoder,
+  getU64Decoder,
+  getU64Encoder,
   getU8Decoder,
   getU8Encoder,
+  none,
   transformEncoder,
   type Address,
   type Codec,
@@ -23,6 +28,8 @@ import {
   type IInstruction,
   type IInstructionWithAccounts,
   type IInstructionWithData,
+  type Option,
+  type OptionOrNullable,
   type ReadonlyAccount,
   type TransactionSigner,
   type WritableAccount,
@@ -93,10 +100,12 @@ export type CreatePolicyInstruction<
 export type CreatePolicyInstructionData = {
   discriminator: number;
   strategy: PermissionStrategy;
+  minBalance: Option<bigint>;
 };
 
 export type CreatePolicyInstructionDataArgs = {
   strategy: PermissionStrategyArgs;
+  minBalance?: OptionOrNullable<number | bigint>;
 };
 
 export function getCreatePolicyInstructionDataEncoder(): Encoder<CreatePolicyInstructionDataArgs> {
@@ -104,8 +113,13 @@ export function getCreatePolicyInstructionDataEncoder(): Encoder<CreatePolicyIns
     getStructEncoder([
       ['discriminator', getU8Encoder()],
       ['strategy', getPermissionStrategyEncoder()],
+      ['minBalance', getOptionEncoder(getU64Encoder())],
     ]),
-    (value) => ({ ...value, discriminator: CREATE_POLICY_DISCRIMINATOR })
+    (value) => ({
+      ...value,
+      discriminator: CREATE_POLICY_DISCRIMINATOR,
+      minBalance: value.minBalance ?? none(),
+    })
   );
 }
 
@@ -113,6 +127,7 @@ export function getCreatePolicyInstructionDataDecoder(): Decoder<CreatePolicyIns
   return getStructDecoder([
     ['discriminator', getU8Decoder()],
     ['strategy', getPermissionStrategyDecoder()],
+    ['minBalance', getOptionDecoder(getU64Decoder())],
   ]);
 }
 
@@ -147,6 +162,7 @@ export type CreatePolicyAsyncInput<
   /** The system program */
   systemProgram?: Address<TAccountSystemProgram>;
   strategy: CreatePolicyInstructionDataArgs['strategy'];
+  minBalance?: CreatePolicyInstructionDataArgs['minBalance'];
 };
 
 export async function getCreatePolicyInstructionAsync<
@@ -267,6 +283,7 @@ export type CreatePolicyInput<
   /** The system program */
   systemProgram?: Address<TAccountSystemProgram>;
   strategy: CreatePolicyInstructionDataArgs['strategy'];
+  minBalance?: CreatePolicyInstructionDataArgs['minBalance'];
 };
 
 export function getCreatePolicyInstruction<
@@ -9,5 +9,10 @@
 export * from './addIdentity';
 export * from './closePolicy';
 export * from './createPolicy';
+export * from './lockPolicy';
+export * from './migratePolicy';
 export * from './removeIdentity';
+export * from './removeIdentityByKey';
 export * from './replaceIdentity';
+export * from './replaceIdentityByKey';
+export * from './setMinBalance';
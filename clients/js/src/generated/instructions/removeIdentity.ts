@@ -8,12 +8,15 @@
 
 import {
   combineCodec,
+  getOptionDecoder,
+  getOptionEncoder,
   getStructDecoder,
   getStructEncoder,
   getU64Decoder,
   getU64Encoder,
   getU8Decoder,
   getU8Encoder,
+  none,
   transformEncoder,
   type Address,
   type Codec,
@@ -24,6 +27,8 @@ import {
   type IInstruction,
   type IInstructionWithAccounts,
   type IInstructionWithData,
+  type Option,
+  type OptionOrNullable,
   type ReadonlyAccount,
   type TransactionSigner,
   type WritableAccount,
@@ -69,17 +74,26 @@ export type RemoveIdentityInstruction<
 export type RemoveIdentityInstructionData = {
   discriminator: number;
   index: bigint;
+  expectedRevision: Option<bigint>;
 };
 
-export type RemoveIdentityInstructionDataArgs = { index: number | bigint };
+export type RemoveIdentityInstructionDataArgs = {
+  index: number | bigint;
+  expectedRevision?: OptionOrNullable<number | bigint>;
+};
 
 export function getRemoveIdentityInstructionDataEncoder(): Encoder<RemoveIdentityInstructionDataArgs> {
   return transformEncoder(
     getStructEncoder([
       ['discriminator', getU8Encoder()],
       ['index', getU64Encoder()],
+      ['expectedRevision', getOptionEncoder(getU64Encoder())],
     ]),
-    (value) => ({ ...value, discriminator: REMOVE_IDENTITY_DISCRIMINATOR })
+    (value) => ({
+      ...value,
+      discriminator: REMOVE_IDENTITY_DISCRIMINATOR,
+      expectedRevision: value.expectedRevision ?? none(),
+    })
   );
 }
 
@@ -87,6 +101,7 @@ export function getRemoveIdentityInstructionDataDecoder(): Decoder<RemoveIdentit
   return getStructDecoder([
     ['discriminator', getU8Decoder()],
     ['index', getU64Decoder()],
+    ['expectedRevision', getOptionDecoder(getU64Decoder())],
   ]);
 }
 
@@ -115,6 +130,7 @@ export type RemoveIdentityInput<
   /** The owner of the token account */
   owner: TransactionSigner<TAccountOwner>;
   index: RemoveIdentityInstructionDataArgs['index'];
+  expectedRevision?: RemoveIdentityInstructionDataArgs['expectedRevision'];
 };
 
 export function getRemoveIdentityInstruction<
@@ -10,12 +10,15 @@ import {
   combineCodec,
   getAddressDecoder,
   getAddressEncoder,
+  getOptionDecoder,
+  getOptionEncoder,
   getStructDecoder,
   getStructEncoder,
   getU64Decoder,
   getU64Encoder,
   getU8Decoder,
   getU8Encoder,
+  none,
   transformEncoder,
   type Address,
   type Codec,
@@ -26,6 +29,8 @@ import {
   type IInstruction,
   type IInstructionWithAccounts,
   type IInstructionWithData,
+  type Option,
+  type OptionOrNullable,
   type ReadonlyAccount,
   type TransactionSigner,
   type WritableAccount,
@@ -72,11 +77,13 @@ export type ReplaceIdentityInstructionData = {
   discriminator: number;
   index: bigint;
   identity: Address;
+  expectedRevision: Option<bigint>;
 };
 
 export type ReplaceIdentityInstructionDataArgs = {
   index: number | bigint;
   identity: Address;
+  expectedRevision?: OptionOrNullable<number | bigint>;
 };
 
 export function getReplaceIdentityInstructionDataEncoder(): Encoder<ReplaceIdentityInstructionDataArgs> {
@@ -85,8 +92,13 @@ export function getReplaceIdentityInstructionDataEncoder(): Encoder<ReplaceIdent
       ['discriminator', getU8Encoder()],
       ['index', getU64Encoder()],
       ['identity', getAddressEncoder()],
+      ['expectedRevision', getOptionEncoder(getU64Encoder())],
     ]),
-    (value) => ({ ...value, discriminator: REPLACE_IDENTITY_DISCRIMINATOR })
+    (value) => ({
+      ...value,
+      discriminator: REPLACE_IDENTITY_DISCRIMINATOR,
+      expectedRevision: value.expectedRevision ?? none(),
+    })
   );
 }
 
@@ -95,6 +107,7 @@ export function getReplaceIdentityInstructionDataDecoder(): Decoder<ReplaceIdent
     ['discriminator', getU8Decoder()],
     ['index', getU64Decoder()],
     ['identity', getAddressDecoder()],
+    ['expectedRevision', getOptionDecoder(getU64Decoder())],
   ]);
 }
 
@@ -124,6 +137,7 @@ export type ReplaceIdentityInput<
   owner: TransactionSigner<TAccountOwner>;
   index: ReplaceIdentityInstructionDataArgs['index'];
   identity: ReplaceIdentityInstructionDataArgs['identity'];
+  expectedRevision?: ReplaceIdentityInstructionDataArgs['expectedRevision'];
 };
 
 export function getReplaceIdentityInstruction<
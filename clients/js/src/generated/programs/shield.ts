@@ -16,8 +16,13 @@ import {
   type ParsedAddIdentityInstruction,
   type ParsedClosePolicyInstruction,
   type ParsedCreatePolicyInstruction,
+  type ParsedLockPolicyInstruction,
+  type ParsedMigratePolicyInstruction,
+  type ParsedRemoveIdentityByKeyInstruction,
   type ParsedRemoveIdentityInstruction,
+  type ParsedReplaceIdentityByKeyInstruction,
   type ParsedReplaceIdentityInstruction,
+  type ParsedSetMinBalanceInstruction,
 } from '../instructions';
 import { Kind } from '../types';
 
@@ -27,6 +32,7 @@ export const SHIELD_PROGRAM_ADDRESS =
 export enum ShieldAccount {
   Policy,
   PolicyV2,
+  PolicyV3,
 }
 
 export function identifyShieldAccount(
@@ -47,6 +53,11 @@ export enum ShieldInstruction {
   RemoveIdentity,
   ReplaceIdentity,
   ClosePolicy,
+  RemoveIdentityByKey,
+  ReplaceIdentityByKey,
+  MigratePolicy,
+  SetMinBalance,
+  LockPolicy,
 }
 
 export function identifyShieldInstruction(
@@ -68,6 +79,21 @@ export function identifyShieldInstruction(
   if (containsBytes(data, getU8Encoder().encode(4), 0)) {
     return ShieldInstruction.ClosePolicy;
   }
+  if (containsBytes(data, getU8Encoder().encode(5), 0)) {
+    return ShieldInstruction.RemoveIdentityByKey;
+  }
+  if (containsBytes(data, getU8Encoder().encode(6), 0)) {
+    return ShieldInstruction.ReplaceIdentityByKey;
+  }
+  if (containsBytes(data, getU8Encoder().encode(7), 0)) {
+    return ShieldInstruction.MigratePolicy;
+  }
+  if (containsBytes(data, getU8Encoder().encode(8), 0)) {
+    return ShieldInstruction.SetMinBalance;
+  }
+  if (containsBytes(data, getU8Encoder().encode(9), 0)) {
+    return ShieldInstruction.LockPolicy;
+  }
   throw new Error(
     'The provided instruction could not be identified as a shield instruction.'
   );
@@ -90,4 +116,19 @@ export type ParsedShieldInstruction<
     } & ParsedReplaceIdentityInstruction<TProgram>)
   | ({
       instructionType: ShieldInstruction.ClosePolicy;
-    } & ParsedClosePolicyInstruction<TProgram>);
+    } & ParsedClosePolicyInstruction<TProgram>)
+  | ({
+      instructionType: ShieldInstruction.RemoveIdentityByKey;
+    } & ParsedRemoveIdentityByKeyInstruction<TProgram>)
+  | ({
+      instructionType: ShieldInstruction.ReplaceIdentityByKey;
+    } & ParsedReplaceIdentityByKeyInstruction<TProgram>)
+  | ({
+      instructionType: ShieldInstruction.MigratePolicy;
+    } & ParsedMigratePolicyInstruction<TProgram>)
+  | ({
+      instructionType: ShieldInstruction.SetMinBalance;
+    } & ParsedSetMinBalanceInstruction<TProgram>)
+  | ({
+      instructionType: ShieldInstruction.LockPolicy;
+    } & ParsedLockPolicyInstruction<TProgram>);
@@ -18,6 +18,7 @@ import {
 export enum Kind {
   Policy,
   PolicyV2,
+  PolicyV3,
 }
 
 export type KindArgs = Kind;
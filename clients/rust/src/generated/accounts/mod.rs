@@ -7,6 +7,8 @@
 
 pub(crate) mod r#policy;
 pub(crate) mod r#policy_v2;
+pub(crate) mod r#policy_v3;
 
 pub use self::r#policy::*;
 pub use self::r#policy_v2::*;
+pub use self::r#policy_v3::*;
@@ -148,6 +148,27 @@ pub enum ShieldError {
     /// 45 - Invalid Index To Reference Identity
     #[error("Invalid Index To Reference Identity")]
     InvalidIndexToReferenceIdentity = 0x2D,
+    /// 46 - Identity already exists
+    #[error("Identity already exists")]
+    IdentityAlreadyExists = 0x2E,
+    /// 47 - Identity slot is empty
+    #[error("Identity slot is empty")]
+    IdentitySlotEmpty = 0x2F,
+    /// 48 - Invalid identity
+    #[error("Invalid identity")]
+    InvalidIdentity = 0x30,
+    /// 49 - Policy revision does not match the expected revision
+    #[error("Policy revision does not match the expected revision")]
+    RevisionMismatch = 0x31,
+    /// 50 - Policy is already at the latest version
+    #[error("Policy is already at the latest version")]
+    PolicyAlreadyMigrated = 0x32,
+    /// 51 - Token balance is below the minimum balance of the policy
+    #[error("Token balance is below the minimum balance of the policy")]
+    InsufficientBalance = 0x33,
+    /// 52 - Policy must be migrated to the latest version
+    #[error("Policy must be migrated to the latest version")]
+    PolicyNotMigrated = 0x34,
 }
 
 impl solana_program_error::PrintProgramError for ShieldError {
@@ -87,6 +87,7 @@ impl Default for AddIdentityInstructionData {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddIdentityInstructionArgs {
     pub identity: Pubkey,
+    pub expected_revision: Option<u64>,
 }
 
 /// Instruction builder for `AddIdentity`.
@@ -108,6 +109,7 @@ pub struct AddIdentityBuilder {
     owner: Option<solana_pubkey::Pubkey>,
     system_program: Option<solana_pubkey::Pubkey>,
     identity: Option<Pubkey>,
+    expected_revision: Option<u64>,
     __remaining_accounts: Vec<solana_instruction::AccountMeta>,
 }
 
@@ -157,6 +159,12 @@ impl AddIdentityBuilder {
         self.identity = Some(identity);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(&mut self, account: solana_instruction::AccountMeta) -> &mut Self {
@@ -186,6 +194,7 @@ impl AddIdentityBuilder {
         };
         let args = AddIdentityInstructionArgs {
             identity: self.identity.clone().expect("identity is not set"),
+            expected_revision: self.expected_revision.clone(),
         };
 
         accounts.instruction_with_remaining_accounts(args, &self.__remaining_accounts)
@@ -352,6 +361,7 @@ impl<'a, 'b> AddIdentityCpiBuilder<'a, 'b> {
             owner: None,
             system_program: None,
             identity: None,
+            expected_revision: None,
             __remaining_accounts: Vec::new(),
         });
         Self { instruction }
@@ -403,6 +413,12 @@ impl<'a, 'b> AddIdentityCpiBuilder<'a, 'b> {
         self.instruction.identity = Some(identity);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.instruction.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(
@@ -446,6 +462,7 @@ impl<'a, 'b> AddIdentityCpiBuilder<'a, 'b> {
                 .identity
                 .clone()
                 .expect("identity is not set"),
+            expected_revision: self.instruction.expected_revision.clone(),
         };
         let instruction = AddIdentityCpi {
             __program: self.instruction.__program,
@@ -486,6 +503,7 @@ struct AddIdentityCpiBuilderInstruction<'a, 'b> {
     owner: Option<&'b solana_account_info::AccountInfo<'a>>,
     system_program: Option<&'b solana_account_info::AccountInfo<'a>>,
     identity: Option<Pubkey>,
+    expected_revision: Option<u64>,
     /// Additional instruction accounts `(AccountInfo, is_writable, is_signer)`.
     __remaining_accounts: Vec<(&'b solana_account_info::AccountInfo<'a>, bool, bool)>,
 }
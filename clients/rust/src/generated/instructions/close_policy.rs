@@ -26,13 +26,14 @@ pub struct ClosePolicy {
 }
 
 impl ClosePolicy {
-    pub fn instruction(&self) -> solana_instruction::Instruction {
-        self.instruction_with_remaining_accounts(&[])
+    pub fn instruction(&self, args: ClosePolicyInstructionArgs) -> solana_instruction::Instruction {
+        self.instruction_with_remaining_accounts(args, &[])
     }
     #[allow(clippy::arithmetic_side_effects)]
     #[allow(clippy::vec_init_then_push)]
     pub fn instruction_with_remaining_accounts(
         &self,
+        args: ClosePolicyInstructionArgs,
         remaining_accounts: &[solana_instruction::AccountMeta],
     ) -> solana_instruction::Instruction {
         let mut accounts = Vec::with_capacity(6 + remaining_accounts.len());
@@ -51,7 +52,9 @@ impl ClosePolicy {
             false,
         ));
         accounts.extend_from_slice(remaining_accounts);
-        let data = borsh::to_vec(&ClosePolicyInstructionData::new()).unwrap();
+        let mut data = borsh::to_vec(&ClosePolicyInstructionData::new()).unwrap();
+        let mut args = borsh::to_vec(&args).unwrap();
+        data.append(&mut args);
 
         solana_instruction::Instruction {
             program_id: crate::SHIELD_ID,
@@ -79,6 +82,12 @@ impl Default for ClosePolicyInstructionData {
     }
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClosePolicyInstructionArgs {
+    pub expected_revision: Option<u64>,
+}
+
 /// Instruction builder for `ClosePolicy`.
 ///
 /// ### Accounts:
@@ -97,6 +106,7 @@ pub struct ClosePolicyBuilder {
     payer: Option<solana_pubkey::Pubkey>,
     owner: Option<solana_pubkey::Pubkey>,
     system_program: Option<solana_pubkey::Pubkey>,
+    expected_revision: Option<u64>,
     __remaining_accounts: Vec<solana_instruction::AccountMeta>,
 }
 
@@ -141,6 +151,12 @@ impl ClosePolicyBuilder {
         self.system_program = Some(system_program);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(&mut self, account: solana_instruction::AccountMeta) -> &mut Self {
@@ -168,8 +184,11 @@ impl ClosePolicyBuilder {
                 .system_program
                 .unwrap_or(solana_pubkey::pubkey!("11111111111111111111111111111111")),
         };
+        let args = ClosePolicyInstructionArgs {
+            expected_revision: self.expected_revision.clone(),
+        };
 
-        accounts.instruction_with_remaining_accounts(&self.__remaining_accounts)
+        accounts.instruction_with_remaining_accounts(args, &self.__remaining_accounts)
     }
 }
 
@@ -205,12 +224,15 @@ pub struct ClosePolicyCpi<'a, 'b> {
     pub owner: &'b solana_account_info::AccountInfo<'a>,
     /// The system program
     pub system_program: &'b solana_account_info::AccountInfo<'a>,
+    /// The arguments for the instruction.
+    pub __args: ClosePolicyInstructionArgs,
 }
 
 impl<'a, 'b> ClosePolicyCpi<'a, 'b> {
     pub fn new(
         program: &'b solana_account_info::AccountInfo<'a>,
         accounts: ClosePolicyCpiAccounts<'a, 'b>,
+        args: ClosePolicyInstructionArgs,
     ) -> Self {
         Self {
             __program: program,
@@ -220,6 +242,7 @@ impl<'a, 'b> ClosePolicyCpi<'a, 'b> {
             payer: accounts.payer,
             owner: accounts.owner,
             system_program: accounts.system_program,
+            __args: args,
         }
     }
     #[inline(always)]
@@ -274,7 +297,9 @@ impl<'a, 'b> ClosePolicyCpi<'a, 'b> {
                 is_writable: remaining_account.2,
             })
         });
-        let data = borsh::to_vec(&ClosePolicyInstructionData::new()).unwrap();
+        let mut data = borsh::to_vec(&ClosePolicyInstructionData::new()).unwrap();
+        let mut args = borsh::to_vec(&self.__args).unwrap();
+        data.append(&mut args);
 
         let instruction = solana_instruction::Instruction {
             program_id: crate::SHIELD_ID,
@@ -326,6 +351,7 @@ impl<'a, 'b> ClosePolicyCpiBuilder<'a, 'b> {
             payer: None,
             owner: None,
             system_program: None,
+            expected_revision: None,
             __remaining_accounts: Vec::new(),
         });
         Self { instruction }
@@ -372,6 +398,12 @@ impl<'a, 'b> ClosePolicyCpiBuilder<'a, 'b> {
         self.instruction.system_program = Some(system_program);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.instruction.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(
@@ -409,6 +441,9 @@ impl<'a, 'b> ClosePolicyCpiBuilder<'a, 'b> {
         &self,
         signers_seeds: &[&[&[u8]]],
     ) -> solana_program_entrypoint::ProgramResult {
+        let args = ClosePolicyInstructionArgs {
+            expected_revision: self.instruction.expected_revision.clone(),
+        };
         let instruction = ClosePolicyCpi {
             __program: self.instruction.__program,
 
@@ -429,6 +464,7 @@ impl<'a, 'b> ClosePolicyCpiBuilder<'a, 'b> {
                 .instruction
                 .system_program
                 .expect("system_program is not set"),
+            __args: args,
         };
         instruction.invoke_signed_with_remaining_accounts(
             signers_seeds,
@@ -446,6 +482,7 @@ struct ClosePolicyCpiBuilderInstruction<'a, 'b> {
     payer: Option<&'b solana_account_info::AccountInfo<'a>>,
     owner: Option<&'b solana_account_info::AccountInfo<'a>>,
     system_program: Option<&'b solana_account_info::AccountInfo<'a>>,
+    expected_revision: Option<u64>,
     /// Additional instruction accounts `(AccountInfo, is_writable, is_signer)`.
     __remaining_accounts: Vec<(&'b solana_account_info::AccountInfo<'a>, bool, bool)>,
 }
@@ -90,6 +90,7 @@ impl Default for CreatePolicyInstructionData {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreatePolicyInstructionArgs {
     pub strategy: PermissionStrategy,
+    pub min_balance: Option<u64>,
 }
 
 /// Instruction builder for `CreatePolicy`.
@@ -111,6 +112,7 @@ pub struct CreatePolicyBuilder {
     owner: Option<solana_pubkey::Pubkey>,
     system_program: Option<solana_pubkey::Pubkey>,
     strategy: Option<PermissionStrategy>,
+    min_balance: Option<u64>,
     __remaining_accounts: Vec<solana_instruction::AccountMeta>,
 }
 
@@ -160,6 +162,12 @@ impl CreatePolicyBuilder {
         self.strategy = Some(strategy);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn min_balance(&mut self, min_balance: u64) -> &mut Self {
+        self.min_balance = Some(min_balance);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(&mut self, account: solana_instruction::AccountMeta) -> &mut Self {
@@ -189,6 +197,7 @@ impl CreatePolicyBuilder {
         };
         let args = CreatePolicyInstructionArgs {
             strategy: self.strategy.clone().expect("strategy is not set"),
+            min_balance: self.min_balance.clone(),
         };
 
         accounts.instruction_with_remaining_accounts(args, &self.__remaining_accounts)
@@ -355,6 +364,7 @@ impl<'a, 'b> CreatePolicyCpiBuilder<'a, 'b> {
             owner: None,
             system_program: None,
             strategy: None,
+            min_balance: None,
             __remaining_accounts: Vec::new(),
         });
         Self { instruction }
@@ -406,6 +416,12 @@ impl<'a, 'b> CreatePolicyCpiBuilder<'a, 'b> {
         self.instruction.strategy = Some(strategy);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn min_balance(&mut self, min_balance: u64) -> &mut Self {
+        self.instruction.min_balance = Some(min_balance);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(
@@ -449,6 +465,7 @@ impl<'a, 'b> CreatePolicyCpiBuilder<'a, 'b> {
                 .strategy
                 .clone()
                 .expect("strategy is not set"),
+            min_balance: self.instruction.min_balance.clone(),
         };
         let instruction = CreatePolicyCpi {
             __program: self.instruction.__program,
@@ -489,6 +506,7 @@ struct CreatePolicyCpiBuilderInstruction<'a, 'b> {
     owner: Option<&'b solana_account_info::AccountInfo<'a>>,
     system_program: Option<&'b solana_account_info::AccountInfo<'a>>,
     strategy: Option<PermissionStrategy>,
+    min_balance: Option<u64>,
     /// Additional instruction accounts `(AccountInfo, is_writable, is_signer)`.
     __remaining_accounts: Vec<(&'b solana_account_info::AccountInfo<'a>, bool, bool)>,
 }
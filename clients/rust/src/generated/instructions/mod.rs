@@ -8,11 +8,21 @@
 pub(crate) mod r#add_identity;
 pub(crate) mod r#close_policy;
 pub(crate) mod r#create_policy;
+pub(crate) mod r#lock_policy;
+pub(crate) mod r#migrate_policy;
 pub(crate) mod r#remove_identity;
+pub(crate) mod r#remove_identity_by_key;
 pub(crate) mod r#replace_identity;
+pub(crate) mod r#replace_identity_by_key;
+pub(crate) mod r#set_min_balance;
 
 pub use self::r#add_identity::*;
 pub use self::r#close_policy::*;
 pub use self::r#create_policy::*;
+pub use self::r#lock_policy::*;
+pub use self::r#migrate_policy::*;
 pub use self::r#remove_identity::*;
+pub use self::r#remove_identity_by_key::*;
 pub use self::r#replace_identity::*;
+pub use self::r#replace_identity_by_key::*;
+pub use self::r#set_min_balance::*;
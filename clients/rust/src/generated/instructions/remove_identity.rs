@@ -80,6 +80,7 @@ impl Default for RemoveIdentityInstructionData {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RemoveIdentityInstructionArgs {
     pub index: u64,
+    pub expected_revision: Option<u64>,
 }
 
 /// Instruction builder for `RemoveIdentity`.
@@ -97,6 +98,7 @@ pub struct RemoveIdentityBuilder {
     policy: Option<solana_pubkey::Pubkey>,
     owner: Option<solana_pubkey::Pubkey>,
     index: Option<u64>,
+    expected_revision: Option<u64>,
     __remaining_accounts: Vec<solana_instruction::AccountMeta>,
 }
 
@@ -133,6 +135,12 @@ impl RemoveIdentityBuilder {
         self.index = Some(index);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(&mut self, account: solana_instruction::AccountMeta) -> &mut Self {
@@ -158,6 +166,7 @@ impl RemoveIdentityBuilder {
         };
         let args = RemoveIdentityInstructionArgs {
             index: self.index.clone().expect("index is not set"),
+            expected_revision: self.expected_revision.clone(),
         };
 
         accounts.instruction_with_remaining_accounts(args, &self.__remaining_accounts)
@@ -303,6 +312,7 @@ impl<'a, 'b> RemoveIdentityCpiBuilder<'a, 'b> {
             policy: None,
             owner: None,
             index: None,
+            expected_revision: None,
             __remaining_accounts: Vec::new(),
         });
         Self { instruction }
@@ -339,6 +349,12 @@ impl<'a, 'b> RemoveIdentityCpiBuilder<'a, 'b> {
         self.instruction.index = Some(index);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.instruction.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(
@@ -378,6 +394,7 @@ impl<'a, 'b> RemoveIdentityCpiBuilder<'a, 'b> {
     ) -> solana_program_entrypoint::ProgramResult {
         let args = RemoveIdentityInstructionArgs {
             index: self.instruction.index.clone().expect("index is not set"),
+            expected_revision: self.instruction.expected_revision.clone(),
         };
         let instruction = RemoveIdentityCpi {
             __program: self.instruction.__program,
@@ -409,6 +426,7 @@ struct RemoveIdentityCpiBuilderInstruction<'a, 'b> {
     policy: Option<&'b solana_account_info::AccountInfo<'a>>,
     owner: Option<&'b solana_account_info::AccountInfo<'a>>,
     index: Option<u64>,
+    expected_revision: Option<u64>,
     /// Additional instruction accounts `(AccountInfo, is_writable, is_signer)`.
     __remaining_accounts: Vec<(&'b solana_account_info::AccountInfo<'a>, bool, bool)>,
 }
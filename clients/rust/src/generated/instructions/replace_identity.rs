@@ -82,6 +82,7 @@ impl Default for ReplaceIdentityInstructionData {
 pub struct ReplaceIdentityInstructionArgs {
     pub index: u64,
     pub identity: Pubkey,
+    pub expected_revision: Option<u64>,
 }
 
 /// Instruction builder for `ReplaceIdentity`.
@@ -100,6 +101,7 @@ pub struct ReplaceIdentityBuilder {
     owner: Option<solana_pubkey::Pubkey>,
     index: Option<u64>,
     identity: Option<Pubkey>,
+    expected_revision: Option<u64>,
     __remaining_accounts: Vec<solana_instruction::AccountMeta>,
 }
 
@@ -141,6 +143,12 @@ impl ReplaceIdentityBuilder {
         self.identity = Some(identity);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(&mut self, account: solana_instruction::AccountMeta) -> &mut Self {
@@ -167,6 +175,7 @@ impl ReplaceIdentityBuilder {
         let args = ReplaceIdentityInstructionArgs {
             index: self.index.clone().expect("index is not set"),
             identity: self.identity.clone().expect("identity is not set"),
+            expected_revision: self.expected_revision.clone(),
         };
 
         accounts.instruction_with_remaining_accounts(args, &self.__remaining_accounts)
@@ -313,6 +322,7 @@ impl<'a, 'b> ReplaceIdentityCpiBuilder<'a, 'b> {
             owner: None,
             index: None,
             identity: None,
+            expected_revision: None,
             __remaining_accounts: Vec::new(),
         });
         Self { instruction }
@@ -354,6 +364,12 @@ impl<'a, 'b> ReplaceIdentityCpiBuilder<'a, 'b> {
         self.instruction.identity = Some(identity);
         self
     }
+    /// `[optional argument]`
+    #[inline(always)]
+    pub fn expected_revision(&mut self, expected_revision: u64) -> &mut Self {
+        self.instruction.expected_revision = Some(expected_revision);
+        self
+    }
     /// Add an additional account to the instruction.
     #[inline(always)]
     pub fn add_remaining_account(
@@ -398,6 +414,7 @@ impl<'a, 'b> ReplaceIdentityCpiBuilder<'a, 'b> {
                 .identity
                 .clone()
                 .expect("identity is not set"),
+            expected_revision: self.instruction.expected_revision.clone(),
         };
         let instruction = ReplaceIdentityCpi {
             __program: self.instruction.__program,
@@ -430,6 +447,7 @@ struct ReplaceIdentityCpiBuilderInstruction<'a, 'b> {
     owner: Option<&'b solana_account_info::AccountInfo<'a>>,
     index: Option<u64>,
     identity: Option<Pubkey>,
+    expected_revision: Option<u64>,
     /// Additional instruction accounts `(AccountInfo, is_writable, is_signer)`.
     __remaining_accounts: Vec<(&'b solana_account_info::AccountInfo<'a>, bool, bool)>,
 }
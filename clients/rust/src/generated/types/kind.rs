@@ -25,4 +25,5 @@ use num_derive::FromPrimitive;
 pub enum Kind {
     Policy,
     PolicyV2,
+    PolicyV3,
 }
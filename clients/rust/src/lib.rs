@@ -2,6 +2,7 @@ mod generated;
 
 pub use generated::programs::SHIELD_ID as ID;
 pub use generated::*;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
 use solana_hash::Hash;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
@@ -12,17 +13,22 @@ use solana_transaction::Transaction;
 use std::str::FromStr;
 
 #[cfg(feature = "token-extensions")]
-use spl_associated_token_account::instruction::create_associated_token_account;
+use spl_associated_token_account::instruction::{
+    create_associated_token_account, create_associated_token_account_idempotent,
+};
 
 #[cfg(feature = "token-extensions")]
 use spl_token_2022::{
     extension::metadata_pointer::instruction::initialize as initialize_metadata_pointer,
-    instruction::{initialize_mint2, mint_to},
+    instruction::{initialize_mint2, mint_to, set_authority, transfer_checked, AuthorityType},
     ID as TOKEN_22_PROGRAM_ID,
 };
 
 #[cfg(feature = "token-extensions")]
-use spl_token_metadata_interface::instruction::initialize as initialize_metadata;
+use spl_token_metadata_interface::{
+    instruction::{initialize as initialize_metadata, update_field as update_metadata_field},
+    state::{Field, TokenMetadata},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
@@ -32,8 +38,16 @@ pub enum ParseError {
     InvalidKind,
     #[error("No mint")]
     NoMint,
+    #[error("No revision")]
+    NoRevision,
+    #[error("No minimum balance")]
+    NoMinBalance,
     #[error("Invalid data")]
     InvalidData,
+    #[error("Invalid event kind")]
+    InvalidEventKind,
+    #[error("Unsupported event version {0}")]
+    UnsupportedEventVersion(u8),
     #[error("IO error occurred: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -69,6 +83,7 @@ impl TryFrom<u8> for generated::types::Kind {
         match value {
             0 => Ok(generated::types::Kind::Policy),
             1 => Ok(generated::types::Kind::PolicyV2),
+            2 => Ok(generated::types::Kind::PolicyV3),
             _ => Err(ParseError::InvalidKind),
         }
     }
@@ -81,6 +96,9 @@ pub trait PolicyTrait {
     fn try_kind(&self) -> Result<generated::types::Kind, ParseError>;
     fn try_strategy(&self) -> Result<generated::types::PermissionStrategy, ParseError>;
     fn try_mint(&self) -> Result<Pubkey, ParseError>;
+    fn try_revision(&self) -> Result<u64, ParseError>;
+    fn try_min_balance(&self) -> Result<u64, ParseError>;
+    fn is_locked(&self) -> bool;
     fn from_bytes(data: &[u8]) -> Result<Self, std::io::Error>
     where
         Self: Sized;
@@ -93,6 +111,18 @@ impl PolicyTrait for generated::accounts::Policy {
         Err(ParseError::NoMint)
     }
 
+    fn try_revision(&self) -> Result<u64, ParseError> {
+        Err(ParseError::NoRevision)
+    }
+
+    fn try_min_balance(&self) -> Result<u64, ParseError> {
+        Err(ParseError::NoMinBalance)
+    }
+
+    fn is_locked(&self) -> bool {
+        false
+    }
+
     fn from_bytes(data: &[u8]) -> Result<Self, std::io::Error> {
         generated::accounts::Policy::from_bytes(&data[..Self::LEN])
     }
@@ -133,6 +163,18 @@ impl PolicyTrait for generated::accounts::PolicyV2 {
         Ok(self.mint)
     }
 
+    fn try_revision(&self) -> Result<u64, ParseError> {
+        Err(ParseError::NoRevision)
+    }
+
+    fn try_min_balance(&self) -> Result<u64, ParseError> {
+        Err(ParseError::NoMinBalance)
+    }
+
+    fn is_locked(&self) -> bool {
+        false
+    }
+
     fn current_identities_len(&self) -> u32 {
         u32::from_le_bytes(self.identities_len)
     }
@@ -166,6 +208,324 @@ impl PolicyTrait for generated::accounts::PolicyV2 {
     }
 }
 
+impl PolicyTrait for generated::accounts::PolicyV3 {
+    const LEN: usize = generated::accounts::PolicyV3::LEN;
+
+    fn try_mint(&self) -> Result<Pubkey, ParseError> {
+        Ok(self.mint)
+    }
+
+    fn try_revision(&self) -> Result<u64, ParseError> {
+        Ok(u64::from_le_bytes(self.revision))
+    }
+
+    fn try_min_balance(&self) -> Result<u64, ParseError> {
+        Ok(u64::from_le_bytes(self.min_balance))
+    }
+
+    fn is_locked(&self) -> bool {
+        self.locked != 0
+    }
+
+    fn current_identities_len(&self) -> u32 {
+        u32::from_le_bytes(self.identities_len)
+    }
+
+    fn try_deserialize_identities(data: &[u8]) -> Result<Vec<Pubkey>, ParseError> {
+        let identities_data = &data[Self::LEN..];
+
+        if identities_data.len() % PUBKEY_BYTES != 0 {
+            return Err(ParseError::InvalidData);
+        }
+
+        let identities = identities_data
+            .chunks_exact(PUBKEY_BYTES)
+            .map(Pubkey::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ParseError::InvalidData)?;
+
+        Ok(identities)
+    }
+
+    fn try_kind(&self) -> Result<generated::types::Kind, ParseError> {
+        generated::types::Kind::try_from(self.kind)
+    }
+
+    fn try_strategy(&self) -> Result<generated::types::PermissionStrategy, ParseError> {
+        generated::types::PermissionStrategy::try_from(self.strategy)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, std::io::Error> {
+        generated::accounts::PolicyV3::from_bytes(&data[..Self::LEN])
+    }
+}
+
+/// A policy account decoded independently of its version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedPolicy {
+    pub kind: generated::types::Kind,
+    pub strategy: generated::types::PermissionStrategy,
+    /// The mint linked to the policy, not recorded by `Policy` accounts.
+    pub mint: Option<Pubkey>,
+    /// The revision of the policy, only recorded by `PolicyV3` accounts.
+    pub revision: Option<u64>,
+    /// The token balance required to mutate the policy, only recorded by `PolicyV3` accounts.
+    pub min_balance: Option<u64>,
+    /// Whether the policy was locked by `LockPolicy`, only `PolicyV3` accounts can be locked.
+    pub locked: bool,
+    /// The identities buffer, including empty slots left by removals.
+    pub identities: Vec<Pubkey>,
+}
+
+impl DecodedPolicy {
+    /// Decode a policy account of any version from its raw data.
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ParseError> {
+        let kind = data.first().ok_or(ParseError::InvalidData)?;
+
+        match generated::types::Kind::try_from(*kind)? {
+            generated::types::Kind::Policy => Self::decode::<generated::accounts::Policy>(data),
+            generated::types::Kind::PolicyV2 => Self::decode::<generated::accounts::PolicyV2>(data),
+            generated::types::Kind::PolicyV3 => Self::decode::<generated::accounts::PolicyV3>(data),
+        }
+    }
+
+    fn decode<T: PolicyTrait>(data: &[u8]) -> Result<Self, ParseError> {
+        if data.len() < T::LEN {
+            return Err(ParseError::InvalidData);
+        }
+
+        let policy = T::from_bytes(data)?;
+
+        Ok(Self {
+            kind: policy.try_kind()?,
+            strategy: policy.try_strategy()?,
+            mint: policy.try_mint().ok(),
+            revision: policy.try_revision().ok(),
+            min_balance: policy.try_min_balance().ok(),
+            locked: policy.is_locked(),
+            identities: T::try_deserialize_identities(data)?,
+        })
+    }
+
+    /// The identities of the policy, skipping empty slots.
+    pub fn active_identities(&self) -> impl Iterator<Item = &Pubkey> {
+        self.identities
+            .iter()
+            .filter(|identity| **identity != Pubkey::default())
+    }
+}
+
+/// Version of the `PolicyEvent` layout logged by the program.
+pub const EVENT_VERSION: u8 = 2;
+
+/// The mutation recorded by a `PolicyEvent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PolicyEventKind {
+    PolicyCreated,
+    IdentityAdded,
+    IdentityRemoved,
+    IdentityReplaced,
+    PolicyClosed,
+    PolicyMigrated,
+    MinBalanceSet,
+    PolicyLocked,
+}
+
+impl TryFrom<u8> for PolicyEventKind {
+    type Error = ParseError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PolicyEventKind::PolicyCreated),
+            1 => Ok(PolicyEventKind::IdentityAdded),
+            2 => Ok(PolicyEventKind::IdentityRemoved),
+            3 => Ok(PolicyEventKind::IdentityReplaced),
+            4 => Ok(PolicyEventKind::PolicyClosed),
+            5 => Ok(PolicyEventKind::PolicyMigrated),
+            6 => Ok(PolicyEventKind::MinBalanceSet),
+            7 => Ok(PolicyEventKind::PolicyLocked),
+            _ => Err(ParseError::InvalidEventKind),
+        }
+    }
+}
+
+/// A mutation of a policy, logged by the program with `sol_log_data` as a `Program data:`
+/// entry.
+///
+/// `identity` and `index` are zeroed by events that do not target an identity slot,
+/// `previous_identity` by events other than `IdentityReplaced`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyEvent {
+    pub kind: PolicyEventKind,
+    pub policy: Pubkey,
+    /// The identity added, removed or written by the mutation.
+    pub identity: Pubkey,
+    /// The identity overwritten by an `IdentityReplaced` mutation, default if the slot was empty.
+    pub previous_identity: Pubkey,
+    /// Slot of the identities buffer holding `identity`.
+    pub index: u32,
+    /// Number of identities of the policy after the mutation, `0` once closed.
+    pub identities_len: u32,
+    /// Revision of the policy after the mutation, `0` for kinds without a revision.
+    pub revision: u64,
+}
+
+impl PolicyEvent {
+    /// Length of a version 2 event: version, kind, policy, identity, previous_identity, index,
+    /// identities_len and revision.
+    pub const LEN: usize = 2 + 3 * PUBKEY_BYTES + 4 + 4 + 8;
+
+    /// Decode an event from the data logged by the program.
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ParseError> {
+        let version = *data.first().ok_or(ParseError::InvalidData)?;
+
+        if version != EVENT_VERSION {
+            return Err(ParseError::UnsupportedEventVersion(version));
+        }
+
+        if data.len() != Self::LEN {
+            return Err(ParseError::InvalidData);
+        }
+
+        let (policy, rest) = data[2..].split_at(PUBKEY_BYTES);
+        let (identity, rest) = rest.split_at(PUBKEY_BYTES);
+        let (previous_identity, rest) = rest.split_at(PUBKEY_BYTES);
+        let (index, rest) = rest.split_at(4);
+        let (identities_len, revision) = rest.split_at(4);
+
+        Ok(Self {
+            kind: PolicyEventKind::try_from(data[1])?,
+            policy: Pubkey::try_from(policy).map_err(|_| ParseError::InvalidData)?,
+            identity: Pubkey::try_from(identity).map_err(|_| ParseError::InvalidData)?,
+            previous_identity: Pubkey::try_from(previous_identity)
+                .map_err(|_| ParseError::InvalidData)?,
+            index: u32::from_le_bytes(index.try_into().map_err(|_| ParseError::InvalidData)?),
+            identities_len: u32::from_le_bytes(
+                identities_len
+                    .try_into()
+                    .map_err(|_| ParseError::InvalidData)?,
+            ),
+            revision: u64::from_le_bytes(revision.try_into().map_err(|_| ParseError::InvalidData)?),
+        })
+    }
+
+    /// Encode the event as logged by the program.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::LEN);
+        data.push(EVENT_VERSION);
+        data.push(self.kind as u8);
+        data.extend_from_slice(self.policy.as_ref());
+        data.extend_from_slice(self.identity.as_ref());
+        data.extend_from_slice(self.previous_identity.as_ref());
+        data.extend_from_slice(&self.index.to_le_bytes());
+        data.extend_from_slice(&self.identities_len.to_le_bytes());
+        data.extend_from_slice(&self.revision.to_le_bytes());
+        data
+    }
+}
+
+/// Keys of the `additional_metadata` of a policy token describing the policy.
+pub mod metadata_keys {
+    /// What the policy is used for.
+    pub const DESCRIPTION: &str = "description";
+    /// Kind of identities listed, e.g. `sandwich` or `spam`.
+    pub const CATEGORY: &str = "category";
+    /// How to reach the maintainer of the policy.
+    pub const CONTACT: &str = "contact";
+    /// How often the identities are updated, e.g. `daily`.
+    pub const UPDATE_CADENCE: &str = "update_cadence";
+    /// Where the identities are sourced from.
+    pub const SOURCE_URL: &str = "source_url";
+}
+
+/// The token metadata of a policy, with the fields of `metadata_keys` read from its
+/// `additional_metadata`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PolicyMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub contact: Option<String>,
+    pub update_cadence: Option<String>,
+    pub source_url: Option<String>,
+}
+
+impl PolicyMetadata {
+    /// Reads the structured fields from `additional_metadata`, ignoring unknown keys.
+    pub fn new(
+        name: String,
+        symbol: String,
+        uri: String,
+        additional_metadata: &[(String, String)],
+    ) -> Self {
+        let field = |key: &str| {
+            additional_metadata
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.clone())
+        };
+
+        Self {
+            description: field(metadata_keys::DESCRIPTION),
+            category: field(metadata_keys::CATEGORY),
+            contact: field(metadata_keys::CONTACT),
+            update_cadence: field(metadata_keys::UPDATE_CADENCE),
+            source_url: field(metadata_keys::SOURCE_URL),
+            name,
+            symbol,
+            uri,
+        }
+    }
+
+    /// The structured fields that are set, as `additional_metadata` entries.
+    pub fn fields(&self) -> Vec<(String, String)> {
+        [
+            (metadata_keys::DESCRIPTION, &self.description),
+            (metadata_keys::CATEGORY, &self.category),
+            (metadata_keys::CONTACT, &self.contact),
+            (metadata_keys::UPDATE_CADENCE, &self.update_cadence),
+            (metadata_keys::SOURCE_URL, &self.source_url),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref().map(|value| (key.to_string(), value.clone())))
+        .collect()
+    }
+
+    /// Decode the metadata of a policy from the raw data of its mint.
+    #[cfg(feature = "token-extensions")]
+    pub fn try_from_mint(data: &[u8]) -> Result<Self, ParseError> {
+        use borsh::BorshDeserialize;
+        use spl_token_2022::{
+            extension::{BaseStateWithExtensions, PodStateWithExtensions},
+            pod::PodMint,
+        };
+
+        let mint =
+            PodStateWithExtensions::<PodMint>::unpack(data).map_err(|_| ParseError::InvalidData)?;
+        let bytes = mint
+            .get_extension_bytes::<TokenMetadata>()
+            .map_err(|_| ParseError::InvalidData)?;
+
+        Ok(Self::from(&TokenMetadata::try_from_slice(bytes)?))
+    }
+}
+
+#[cfg(feature = "token-extensions")]
+impl From<&TokenMetadata> for PolicyMetadata {
+    fn from(metadata: &TokenMetadata) -> Self {
+        Self::new(
+            metadata.name.clone(),
+            metadata.symbol.clone(),
+            metadata.uri.clone(),
+            &metadata.additional_metadata,
+        )
+    }
+}
+
 /// Instruction builder for creating a solana account.
 ///
 /// ### Accounts:
@@ -359,6 +719,7 @@ pub struct CreateAsscoiatedTokenAccountBuilder<'a> {
     mint: Option<&'a Pubkey>,
     owner: Option<&'a Pubkey>,
     payer: Option<&'a Pubkey>,
+    idempotent: bool,
 }
 
 #[cfg(feature = "token-extensions")]
@@ -369,6 +730,7 @@ impl<'a> CreateAsscoiatedTokenAccountBuilder<'a> {
             mint: None,
             owner: None,
             payer: None,
+            idempotent: false,
         }
     }
 
@@ -399,13 +761,24 @@ impl<'a> CreateAsscoiatedTokenAccountBuilder<'a> {
         self
     }
 
+    /// Succeed without changes when the account already exists
+    #[inline(always)]
+    pub fn idempotent(&mut self, idempotent: bool) -> &mut Self {
+        self.idempotent = idempotent;
+        self
+    }
+
     pub fn instruction(&self) -> Instruction {
         let owner = self.owner.expect("owner is not set");
         let mint = self.mint.expect("mint is not set");
         let payer = self.payer.expect("payer is not set");
         let token_program = self.token_program.unwrap_or(&TOKEN_22_PROGRAM_ID);
 
-        create_associated_token_account(payer, owner, mint, token_program)
+        if self.idempotent {
+            create_associated_token_account_idempotent(payer, owner, mint, token_program)
+        } else {
+            create_associated_token_account(payer, owner, mint, token_program)
+        }
     }
 }
 
@@ -487,11 +860,170 @@ impl<'a> TokenExtensionsMintToBuilder<'a> {
     }
 }
 
+#[cfg(feature = "token-extensions")]
+pub struct TokenExtensionsTransferBuilder<'a> {
+    token_program: Option<&'a Pubkey>,
+    mint: Option<&'a Pubkey>,
+    source: Option<&'a Pubkey>,
+    destination: Option<&'a Pubkey>,
+    owner: Option<&'a Pubkey>,
+    signers: Vec<&'a Pubkey>,
+    amount: u64,
+    decimals: u8,
+}
+
+#[cfg(feature = "token-extensions")]
+impl<'a> TokenExtensionsTransferBuilder<'a> {
+    pub fn build() -> Self {
+        Self {
+            token_program: None,
+            mint: None,
+            source: None,
+            destination: None,
+            owner: None,
+            signers: Vec::new(),
+            amount: 1,
+            decimals: 0,
+        }
+    }
+
+    /// The token program
+    #[inline(always)]
+    pub fn token_program(&mut self, token_program: &'a Pubkey) -> &mut Self {
+        self.token_program = Some(token_program);
+        self
+    }
+
+    /// The mint account
+    #[inline(always)]
+    pub fn mint(&mut self, mint: &'a Pubkey) -> &mut Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// The account to transfer from
+    #[inline(always)]
+    pub fn source(&mut self, source: &'a Pubkey) -> &mut Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// The account to transfer to
+    #[inline(always)]
+    pub fn destination(&mut self, destination: &'a Pubkey) -> &mut Self {
+        self.destination = Some(destination);
+        self
+    }
+
+    /// The owner of the source account
+    #[inline(always)]
+    pub fn owner(&mut self, owner: &'a Pubkey) -> &mut Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Add a signer
+    #[inline(always)]
+    pub fn add_signer(&mut self, signer: &'a Pubkey) -> &mut Self {
+        self.signers.push(signer);
+        self
+    }
+
+    /// The amount to transfer
+    #[inline(always)]
+    pub fn amount(&mut self, amount: u64) -> &mut Self {
+        self.amount = amount;
+        self
+    }
+
+    /// The decimals of the mint, `0` for policy mints
+    #[inline(always)]
+    pub fn decimals(&mut self, decimals: u8) -> &mut Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        transfer_checked(
+            self.token_program.unwrap_or(&TOKEN_22_PROGRAM_ID),
+            self.source.expect("source is not set"),
+            self.mint.expect("mint is not set"),
+            self.destination.expect("destination is not set"),
+            self.owner.expect("owner is not set"),
+            &self.signers,
+            self.amount,
+            self.decimals,
+        )
+        .expect("Failed to create transfer checked instruction")
+    }
+}
+
+/// Instruction builder removing the mint authority of a mint, so no more tokens can be minted.
+#[cfg(feature = "token-extensions")]
+pub struct RevokeMintAuthorityBuilder<'a> {
+    token_program: Option<&'a Pubkey>,
+    mint: Option<&'a Pubkey>,
+    mint_authority: Option<&'a Pubkey>,
+}
+
+#[cfg(feature = "token-extensions")]
+impl<'a> RevokeMintAuthorityBuilder<'a> {
+    pub fn build() -> Self {
+        Self {
+            token_program: None,
+            mint: None,
+            mint_authority: None,
+        }
+    }
+
+    /// The token program
+    #[inline(always)]
+    pub fn token_program(&mut self, token_program: &'a Pubkey) -> &mut Self {
+        self.token_program = Some(token_program);
+        self
+    }
+
+    /// The mint account
+    #[inline(always)]
+    pub fn mint(&mut self, mint: &'a Pubkey) -> &mut Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// The current mint authority
+    #[inline(always)]
+    pub fn mint_authority(&mut self, mint_authority: &'a Pubkey) -> &mut Self {
+        self.mint_authority = Some(mint_authority);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        set_authority(
+            self.token_program.unwrap_or(&TOKEN_22_PROGRAM_ID),
+            self.mint.expect("mint is not set"),
+            None,
+            AuthorityType::MintTokens,
+            self.mint_authority.expect("mint_authority is not set"),
+            &[],
+        )
+        .expect("Failed to create set authority instruction")
+    }
+}
+
+/// Maximum compute units a transaction can request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Margin added to the compute units consumed by a simulation, in percent, so the estimated
+/// limit still covers the transaction when the accounts it touches change slightly.
+pub const COMPUTE_UNIT_MARGIN_PERCENT: u32 = 10;
+
 pub struct TransactionBuilder<'a> {
     instructions: Vec<Instruction>,
     signers: Vec<&'a Keypair>,
     payer: Option<&'a Pubkey>,
     recent_blockhash: Option<Hash>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
 }
 
 impl<'a> TransactionBuilder<'a> {
@@ -501,6 +1033,8 @@ impl<'a> TransactionBuilder<'a> {
             signers: Vec::new(),
             payer: None,
             recent_blockhash: None,
+            compute_unit_limit: None,
+            compute_unit_price: None,
         }
     }
 
@@ -539,15 +1073,105 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
+    /// Set the maximum compute units the transaction can consume
+    #[inline(always)]
+    pub fn compute_unit_limit(&mut self, units: u32) -> &mut Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    /// Set the priority fee paid per compute unit, in micro-lamports
+    #[inline(always)]
+    pub fn compute_unit_price(&mut self, micro_lamports: u64) -> &mut Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// The instructions of the transaction, preceded by the compute budget instructions
+    pub fn transaction_instructions(&self) -> Vec<Instruction> {
+        self.instructions_with_limit(self.compute_unit_limit)
+    }
+
+    fn instructions_with_limit(&self, compute_unit_limit: Option<u32>) -> Vec<Instruction> {
+        let mut instructions = Vec::with_capacity(self.instructions.len() + 2);
+
+        if let Some(units) = compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+
+        if let Some(micro_lamports) = self.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                micro_lamports,
+            ));
+        }
+
+        instructions.extend(self.instructions.iter().cloned());
+        instructions
+    }
+
     /// Build the transaction
     pub fn transaction(&self) -> Transaction {
         Transaction::new_signed_with_payer(
-            &self.instructions,
+            &self.transaction_instructions(),
             self.payer,
             &self.signers,
             self.recent_blockhash.expect("recent blockhash is not set"),
         )
     }
+
+    /// Build the transaction signed only by the signers set on the builder
+    ///
+    /// The signatures of the remaining signers are left empty so the transaction can be
+    /// signed externally, e.g. by a hardware wallet or a multisig.
+    pub fn partial_transaction(&self) -> Transaction {
+        let recent_blockhash = self.recent_blockhash.expect("recent blockhash is not set");
+        let mut transaction =
+            Transaction::new_with_payer(&self.transaction_instructions(), self.payer);
+        transaction.partial_sign(&self.signers, recent_blockhash);
+        transaction
+    }
+
+    /// Estimate the compute unit limit of the transaction by simulating it
+    ///
+    /// The transaction is simulated with the maximum limit, without verifying signatures,
+    /// and the units it consumed are returned with a [`COMPUTE_UNIT_MARGIN_PERCENT`] margin.
+    #[cfg(feature = "fetch")]
+    pub async fn estimate_compute_unit_limit(
+        &self,
+        rpc: &solana_client::nonblocking::rpc_client::RpcClient,
+    ) -> Result<u32, solana_client::client_error::ClientError> {
+        use solana_client::{
+            client_error::ClientErrorKind, rpc_config::RpcSimulateTransactionConfig,
+        };
+
+        let transaction = Transaction::new_with_payer(
+            &self.instructions_with_limit(Some(MAX_COMPUTE_UNIT_LIMIT)),
+            self.payer,
+        );
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..RpcSimulateTransactionConfig::default()
+        };
+
+        let simulation = rpc
+            .simulate_transaction_with_config(&transaction, config)
+            .await?
+            .value;
+
+        if let Some(err) = simulation.err {
+            return Err(err.into());
+        }
+
+        let units = simulation.units_consumed.ok_or_else(|| {
+            ClientErrorKind::Custom("simulation did not report the compute units consumed".into())
+        })?;
+        let units = u32::try_from(units)
+            .unwrap_or(MAX_COMPUTE_UNIT_LIMIT)
+            .min(MAX_COMPUTE_UNIT_LIMIT);
+
+        Ok((units + units * COMPUTE_UNIT_MARGIN_PERCENT / 100).min(MAX_COMPUTE_UNIT_LIMIT))
+    }
 }
 
 #[cfg(feature = "token-extensions")]
@@ -638,3 +1262,125 @@ impl<'a> InitializeMetadataBuilder<'a> {
         )
     }
 }
+
+/// Sets a field of the token metadata of a mint, adding the key to the additional metadata
+/// when missing.
+#[cfg(feature = "token-extensions")]
+#[derive(Clone, Debug, Default)]
+pub struct UpdateMetadataFieldBuilder<'a> {
+    token_program: Option<&'a Pubkey>,
+    mint: Option<&'a Pubkey>,
+    update_authority: Option<&'a Pubkey>,
+    field: Option<Field>,
+    value: Option<String>,
+}
+
+#[cfg(feature = "token-extensions")]
+impl<'a> UpdateMetadataFieldBuilder<'a> {
+    pub fn build() -> Self {
+        Self::default()
+    }
+
+    /// The token program
+    #[inline(always)]
+    pub fn token_program(&mut self, token_program: &'a Pubkey) -> &mut Self {
+        self.token_program = Some(token_program);
+        self
+    }
+
+    /// The mint account holding the metadata
+    #[inline(always)]
+    pub fn mint(&mut self, mint: &'a Pubkey) -> &mut Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// The update authority of the metadata
+    #[inline(always)]
+    pub fn update_authority(&mut self, update_authority: &'a Pubkey) -> &mut Self {
+        self.update_authority = Some(update_authority);
+        self
+    }
+
+    /// The field to update, `Field::Key` adds the key to the additional metadata if missing
+    #[inline(always)]
+    pub fn field(&mut self, field: Field) -> &mut Self {
+        self.field = Some(field);
+        self
+    }
+
+    /// The new value of the field
+    #[inline(always)]
+    pub fn value(&mut self, value: String) -> &mut Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        update_metadata_field(
+            self.token_program.unwrap_or(&TOKEN_22_PROGRAM_ID),
+            self.mint.expect("mint is not set"),
+            self.update_authority.expect("update_authority is not set"),
+            self.field.as_ref().expect("field is not set").clone(),
+            self.value.as_ref().expect("value is not set").clone(),
+        )
+    }
+}
+
+/// Tops up the lamports of an account to the rent exempt minimum of a new size, so the
+/// account can be reallocated by its owner program.
+#[derive(Clone, Debug, Default)]
+pub struct RentTopUpBuilder<'a> {
+    payer: Option<&'a Pubkey>,
+    account: Option<&'a Pubkey>,
+    lamports: Option<u64>,
+    space: Option<usize>,
+}
+
+impl<'a> RentTopUpBuilder<'a> {
+    pub fn build() -> Self {
+        Self::default()
+    }
+
+    /// The account paying for the storage fees
+    #[inline(always)]
+    pub fn payer(&mut self, payer: &'a Pubkey) -> &mut Self {
+        self.payer = Some(payer);
+        self
+    }
+
+    /// The account to be reallocated
+    #[inline(always)]
+    pub fn account(&mut self, account: &'a Pubkey) -> &mut Self {
+        self.account = Some(account);
+        self
+    }
+
+    /// The current lamports of the account
+    #[inline(always)]
+    pub fn lamports(&mut self, lamports: u64) -> &mut Self {
+        self.lamports = Some(lamports);
+        self
+    }
+
+    /// The space of the account once reallocated
+    #[inline(always)]
+    pub fn space(&mut self, space: usize) -> &mut Self {
+        self.space = Some(space);
+        self
+    }
+
+    /// Returns `None` when the account already holds enough lamports for the new size.
+    pub fn instruction(&self) -> Option<Instruction> {
+        let required = Rent::default().minimum_balance(self.space.expect("space is not set"));
+        let missing = required.saturating_sub(self.lamports.expect("lamports is not set"));
+
+        (missing > 0).then(|| {
+            system_instruction::transfer(
+                self.payer.expect("payer is not set"),
+                self.account.expect("account is not set"),
+                missing,
+            )
+        })
+    }
+}
@@ -0,0 +1,959 @@
+#![cfg(feature = "test-sbf")]
+use std::collections::HashSet;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_account::{Account, AccountSharedData};
+use solana_instruction::{error::InstructionError, Instruction};
+use solana_keypair::Keypair;
+use solana_program_test::{tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction_error::TransactionError;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_pod::optional_keys::OptionalNonZeroPubkey;
+use spl_token_2022::{extension::ExtensionType, state::Mint};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use yellowstone_shield_client::{
+    accounts::PolicyV3,
+    errors::ShieldError,
+    instructions::{
+        AddIdentityBuilder, ClosePolicyBuilder, CreatePolicyBuilder, LockPolicyBuilder,
+        MigratePolicyBuilder, RemoveIdentityBuilder, RemoveIdentityByKeyBuilder,
+        ReplaceIdentityBuilder, ReplaceIdentityByKeyBuilder, SetMinBalanceBuilder,
+    },
+    types::{Kind, PermissionStrategy},
+    CreateAccountBuilder, CreateAsscoiatedTokenAccountBuilder, DecodedPolicy,
+    InitializeMetadataBuilder, InitializeMint2Builder, MetadataPointerInitializeBuilder,
+    PolicyEvent, PolicyEventKind, PolicyTrait, TokenExtensionsMintToBuilder, TransactionBuilder,
+};
+
+/// Number of random operations applied by the property test.
+const OPERATIONS: usize = 200;
+
+/// A policy created for a test, owned by the payer of the test context.
+struct TestPolicy {
+    mint: Pubkey,
+    token_account: Pubkey,
+    address: Pubkey,
+}
+
+impl TestPolicy {
+    async fn create(context: &mut ProgramTestContext) -> Self {
+        Self::try_create(context, |create_policy| create_policy.instruction())
+            .await
+            .unwrap()
+    }
+
+    /// Creates a deny policy whose mint has a supply of one token held by the payer.
+    /// `create_policy` builds the `CreatePolicy` instruction from the prefilled builder.
+    async fn try_create(
+        context: &mut ProgramTestContext,
+        create_policy: impl FnOnce(&mut CreatePolicyBuilder) -> Instruction,
+    ) -> Result<Self, BanksClientError> {
+        let mint = Keypair::new();
+        let payer = context.payer.pubkey();
+
+        let token_account = get_associated_token_address_with_program_id(
+            &payer,
+            &mint.pubkey(),
+            &spl_token_2022::ID,
+        );
+        let mint_size =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MetadataPointer])
+                .unwrap();
+
+        let token_metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(payer)).unwrap(),
+            mint: mint.pubkey(),
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            uri: "https://test.com".to_string(),
+            ..Default::default()
+        };
+        let rent = mint_size + token_metadata.tlv_size_of().unwrap();
+
+        let address = PolicyV3::find_pda(&mint.pubkey()).0;
+
+        let create_policy = create_policy(
+            CreatePolicyBuilder::new()
+                .policy(address)
+                .mint(mint.pubkey())
+                .payer(payer)
+                .token_account(token_account)
+                .owner(payer)
+                .strategy(PermissionStrategy::Deny),
+        );
+
+        let tx = TransactionBuilder::build()
+            .instruction(
+                CreateAccountBuilder::build()
+                    .payer(&payer)
+                    .account(&mint.pubkey())
+                    .space(mint_size)
+                    .rent(rent)
+                    .owner(&spl_token_2022::id())
+                    .instruction(),
+            )
+            .instruction(
+                MetadataPointerInitializeBuilder::build()
+                    .mint(&mint.pubkey())
+                    .metadata(mint.pubkey())
+                    .authority(payer)
+                    .instruction(),
+            )
+            .instruction(
+                InitializeMint2Builder::build()
+                    .mint(&mint.pubkey())
+                    .mint_authority(&payer)
+                    .freeze_authority(&payer)
+                    .instruction(),
+            )
+            .instruction(
+                InitializeMetadataBuilder::new()
+                    .mint(&mint.pubkey())
+                    .owner(&payer)
+                    .update_authority(&payer)
+                    .mint_authority(&payer)
+                    .name(token_metadata.name)
+                    .symbol(token_metadata.symbol)
+                    .uri(token_metadata.uri)
+                    .instruction(),
+            )
+            .instruction(
+                CreateAsscoiatedTokenAccountBuilder::build()
+                    .mint(&mint.pubkey())
+                    .owner(&payer)
+                    .payer(&payer)
+                    .instruction(),
+            )
+            .instruction(
+                TokenExtensionsMintToBuilder::build()
+                    .mint(&mint.pubkey())
+                    .account(&token_account)
+                    .owner(&payer)
+                    .amount(1)
+                    .instruction(),
+            )
+            .instruction(create_policy)
+            .signer(&context.payer)
+            .signer(&mint)
+            .payer(&payer)
+            .recent_blockhash(context.last_blockhash)
+            .transaction();
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(Self {
+            mint: mint.pubkey(),
+            token_account,
+            address,
+        })
+    }
+
+    fn add(&self, context: &ProgramTestContext, identity: Pubkey) -> Instruction {
+        AddIdentityBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .payer(context.payer.pubkey())
+            .owner(context.payer.pubkey())
+            .identity(identity)
+            .instruction()
+    }
+
+    fn remove(&self, context: &ProgramTestContext, index: usize) -> Instruction {
+        RemoveIdentityBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .owner(context.payer.pubkey())
+            .index(index as u64)
+            .instruction()
+    }
+
+    fn replace(&self, context: &ProgramTestContext, index: usize, identity: Pubkey) -> Instruction {
+        ReplaceIdentityBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .owner(context.payer.pubkey())
+            .index(index as u64)
+            .identity(identity)
+            .instruction()
+    }
+
+    fn remove_by_key(&self, context: &ProgramTestContext, identity: Pubkey) -> Instruction {
+        RemoveIdentityByKeyBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .owner(context.payer.pubkey())
+            .identity(identity)
+            .instruction()
+    }
+
+    fn replace_by_key(
+        &self,
+        context: &ProgramTestContext,
+        old: Pubkey,
+        new: Pubkey,
+    ) -> Instruction {
+        ReplaceIdentityByKeyBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .owner(context.payer.pubkey())
+            .old_identity(old)
+            .new_identity(new)
+            .instruction()
+    }
+
+    fn set_min_balance(&self, context: &ProgramTestContext, min_balance: u64) -> Instruction {
+        SetMinBalanceBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .owner(context.payer.pubkey())
+            .min_balance(min_balance)
+            .instruction()
+    }
+
+    fn lock(&self, context: &ProgramTestContext) -> Instruction {
+        LockPolicyBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .owner(context.payer.pubkey())
+            .instruction()
+    }
+
+    fn close(&self, context: &ProgramTestContext) -> Instruction {
+        ClosePolicyBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .payer(context.payer.pubkey())
+            .owner(context.payer.pubkey())
+            .instruction()
+    }
+
+    fn mint_to(&self, context: &ProgramTestContext, amount: u64) -> Instruction {
+        TokenExtensionsMintToBuilder::build()
+            .mint(&self.mint)
+            .account(&self.token_account)
+            .owner(&context.payer.pubkey())
+            .amount(amount)
+            .instruction()
+    }
+
+    /// Overwrites the token balance of the payer, as if tokens were transferred away.
+    async fn set_balance(&self, context: &mut ProgramTestContext, amount: u64) {
+        let mut account = context
+            .banks_client
+            .get_account(self.token_account)
+            .await
+            .unwrap()
+            .unwrap();
+        // The amount follows the mint and owner in the token account layout.
+        account.data[64..72].copy_from_slice(&amount.to_le_bytes());
+
+        context.set_account(&self.token_account, &AccountSharedData::from(account));
+    }
+
+    fn migrate(&self, context: &ProgramTestContext) -> Instruction {
+        MigratePolicyBuilder::new()
+            .policy(self.address)
+            .mint(self.mint)
+            .token_account(self.token_account)
+            .payer(context.payer.pubkey())
+            .owner(context.payer.pubkey())
+            .instruction()
+    }
+
+    async fn fetch(&self, context: &mut ProgramTestContext) -> (u32, DecodedPolicy) {
+        let account = context
+            .banks_client
+            .get_account(self.address)
+            .await
+            .unwrap()
+            .unwrap();
+        let header = PolicyV3::from_bytes(&account.data).unwrap();
+
+        (
+            header.current_identities_len(),
+            DecodedPolicy::try_from_bytes(&account.data).unwrap(),
+        )
+    }
+}
+
+/// Sends `instruction` in its own transaction.
+///
+/// `nonce` is used as the compute unit price so identical instructions sent with the same
+/// blockhash still produce distinct transactions.
+async fn send(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    nonce: u64,
+) -> Result<(), BanksClientError> {
+    let tx = TransactionBuilder::build()
+        .instruction(instruction)
+        .compute_unit_price(nonce)
+        .signer(&context.payer)
+        .payer(&context.payer.pubkey())
+        .recent_blockhash(context.last_blockhash)
+        .transaction();
+
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Sends `instruction` in its own transaction and returns the policy events it logged.
+async fn send_with_events(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    nonce: u64,
+) -> Vec<PolicyEvent> {
+    let tx = TransactionBuilder::build()
+        .instruction(instruction)
+        .compute_unit_price(nonce)
+        .signer(&context.payer)
+        .payer(&context.payer.pubkey())
+        .recent_blockhash(context.last_blockhash)
+        .transaction();
+
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    result
+        .metadata
+        .unwrap()
+        .log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .map(|data| PolicyEvent::try_from_bytes(&STANDARD.decode(data).unwrap()).unwrap())
+        .collect()
+}
+
+fn assert_shield_error(result: Result<(), BanksClientError>, expected: ShieldError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, expected as u32)
+        }
+        err => panic!("expected {:?}, got {:?}", expected, err),
+    }
+}
+
+/// Deterministic xorshift generator, so a failing sequence can be replayed from its seed.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+#[tokio::test]
+async fn test_rejects_duplicate_default_and_empty_slot() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let first = Pubkey::new_unique();
+    let second = Pubkey::new_unique();
+
+    send(&mut context, policy.add(&context, first), 0)
+        .await
+        .unwrap();
+    send(&mut context, policy.add(&context, second), 0)
+        .await
+        .unwrap();
+
+    // Adding or replacing with an identity already in the policy.
+    let result = send(&mut context, policy.add(&context, first), 1).await;
+    assert_shield_error(result, ShieldError::IdentityAlreadyExists);
+    let result = send(&mut context, policy.replace(&context, 0, second), 1).await;
+    assert_shield_error(result, ShieldError::IdentityAlreadyExists);
+
+    // The default public key marks empty slots and is never an identity.
+    let result = send(&mut context, policy.add(&context, Pubkey::default()), 2).await;
+    assert_shield_error(result, ShieldError::InvalidIdentity);
+    let result = send(
+        &mut context,
+        policy.replace(&context, 0, Pubkey::default()),
+        2,
+    )
+    .await;
+    assert_shield_error(result, ShieldError::InvalidIdentity);
+
+    // Removing a slot twice.
+    send(&mut context, policy.remove(&context, 0), 3)
+        .await
+        .unwrap();
+    let result = send(&mut context, policy.remove(&context, 0), 4).await;
+    assert_shield_error(result, ShieldError::IdentitySlotEmpty);
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 1);
+    assert_eq!(decoded.identities, vec![Pubkey::default(), second]);
+}
+
+#[tokio::test]
+async fn test_remove_and_replace_by_key() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let (first, second, third, fourth) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    for identity in [first, second, third] {
+        send(&mut context, policy.add(&context, identity), 0)
+            .await
+            .unwrap();
+    }
+
+    // Identities that are not in the policy are reported instead of touching another slot.
+    let result = send(&mut context, policy.remove_by_key(&context, fourth), 1).await;
+    assert_shield_error(result, ShieldError::IdentityNotFound);
+    let result = send(
+        &mut context,
+        policy.replace_by_key(&context, fourth, first),
+        1,
+    )
+    .await;
+    assert_shield_error(result, ShieldError::IdentityAlreadyExists);
+    let unknown = Pubkey::new_unique();
+    let result = send(
+        &mut context,
+        policy.replace_by_key(&context, unknown, fourth),
+        1,
+    )
+    .await;
+    assert_shield_error(result, ShieldError::IdentityNotFound);
+
+    send(&mut context, policy.remove_by_key(&context, second), 2)
+        .await
+        .unwrap();
+    let result = send(&mut context, policy.remove_by_key(&context, second), 3).await;
+    assert_shield_error(result, ShieldError::IdentityNotFound);
+
+    send(
+        &mut context,
+        policy.replace_by_key(&context, third, fourth),
+        4,
+    )
+    .await
+    .unwrap();
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 2);
+    assert_eq!(decoded.identities, vec![first, Pubkey::default(), fourth]);
+
+    // Replacing the default public key fills the first empty slot.
+    send(
+        &mut context,
+        policy.replace_by_key(&context, Pubkey::default(), second),
+        5,
+    )
+    .await
+    .unwrap();
+    let result = send(
+        &mut context,
+        policy.replace_by_key(&context, Pubkey::default(), third),
+        6,
+    )
+    .await;
+    assert_shield_error(result, ShieldError::IdentityNotFound);
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 3);
+    assert_eq!(decoded.identities, vec![first, second, fourth]);
+}
+
+#[tokio::test]
+async fn test_expected_revision() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let (_, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(decoded.revision, Some(0));
+
+    // Instructions without an expected revision are applied whatever the revision.
+    send(&mut context, policy.add(&context, first), 0)
+        .await
+        .unwrap();
+
+    let (_, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(decoded.revision, Some(1));
+
+    // A stale revision is rejected and leaves the policy untouched.
+    let mut add = AddIdentityBuilder::new();
+    add.policy(policy.address)
+        .mint(policy.mint)
+        .token_account(policy.token_account)
+        .payer(context.payer.pubkey())
+        .owner(context.payer.pubkey())
+        .identity(second)
+        .expected_revision(0);
+    let result = send(&mut context, add.instruction(), 1).await;
+    assert_shield_error(result, ShieldError::RevisionMismatch);
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 1);
+    assert_eq!(decoded.revision, Some(1));
+
+    add.expected_revision(1);
+    send(&mut context, add.instruction(), 2).await.unwrap();
+
+    send(&mut context, policy.remove_by_key(&context, first), 3)
+        .await
+        .unwrap();
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 1);
+    assert_eq!(decoded.revision, Some(3));
+    assert_eq!(decoded.identities, vec![Pubkey::default(), second]);
+}
+
+/// Strips the trailing `None` of `instruction`, giving the data layout of clients built before
+/// the instruction gained its optional argument.
+fn legacy(mut instruction: Instruction) -> Instruction {
+    assert_eq!(instruction.data.pop(), Some(0));
+    instruction
+}
+
+#[tokio::test]
+async fn test_legacy_instruction_data() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let add = legacy(policy.add(&context, first));
+    assert_eq!(add.data, [&[1][..], first.as_ref()].concat());
+    send(&mut context, add, 0).await.unwrap();
+
+    let replace = legacy(policy.replace(&context, 0, second));
+    assert_eq!(
+        replace.data,
+        [&[3][..], &0u64.to_le_bytes(), second.as_ref()].concat()
+    );
+    send(&mut context, replace, 1).await.unwrap();
+
+    let remove = legacy(policy.remove(&context, 0));
+    assert_eq!(remove.data, [&[2][..], &0u64.to_le_bytes()].concat());
+    send(&mut context, remove, 2).await.unwrap();
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 0);
+    assert_eq!(decoded.identities, vec![Pubkey::default()]);
+    assert_eq!(decoded.revision, Some(3));
+
+    // Data shorter than the legacy layout is still rejected.
+    let mut truncated = legacy(policy.add(&context, first));
+    truncated.data.pop();
+    assert!(send(&mut context, truncated, 3).await.is_err());
+
+    let close = legacy(policy.close(&context));
+    assert_eq!(close.data, vec![4]);
+    send(&mut context, close, 4).await.unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(policy.address)
+        .await
+        .unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn test_min_balance() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let (_, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(decoded.min_balance, Some(0));
+
+    // The owner holds a single token, it cannot require two.
+    let result = send(&mut context, policy.set_min_balance(&context, 2), 0).await;
+    assert_shield_error(result, ShieldError::InsufficientBalance);
+
+    send(&mut context, policy.mint_to(&context, 1), 1)
+        .await
+        .unwrap();
+    send(&mut context, policy.set_min_balance(&context, 2), 2)
+        .await
+        .unwrap();
+
+    let (_, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(decoded.min_balance, Some(2));
+    assert_eq!(decoded.revision, Some(1));
+
+    send(&mut context, policy.add(&context, first), 3)
+        .await
+        .unwrap();
+
+    // Below the threshold every mutation fails, lowering it included.
+    policy.set_balance(&mut context, 1).await;
+    let result = send(
+        &mut context,
+        policy.replace_by_key(&context, first, second),
+        4,
+    )
+    .await;
+    assert_shield_error(result, ShieldError::InsufficientBalance);
+
+    let result = send(&mut context, policy.set_min_balance(&context, 0), 5).await;
+    assert_shield_error(result, ShieldError::InsufficientBalance);
+
+    policy.set_balance(&mut context, 2).await;
+    send(&mut context, policy.set_min_balance(&context, 0), 6)
+        .await
+        .unwrap();
+    policy.set_balance(&mut context, 1).await;
+    send(
+        &mut context,
+        policy.replace_by_key(&context, first, second),
+        7,
+    )
+    .await
+    .unwrap();
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 1);
+    assert_eq!(decoded.min_balance, Some(0));
+    assert_eq!(decoded.identities, vec![second]);
+}
+
+#[tokio::test]
+async fn test_create_policy_min_balance() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+
+    // The creator holds the single token minted, below the requested threshold.
+    let result = TestPolicy::try_create(&mut context, |create_policy| {
+        create_policy.min_balance(2).instruction()
+    })
+    .await;
+    assert_shield_error(result.map(|_| ()), ShieldError::InsufficientBalance);
+
+    let policy = TestPolicy::try_create(&mut context, |create_policy| {
+        create_policy.min_balance(1).instruction()
+    })
+    .await
+    .unwrap();
+    let (_, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(decoded.min_balance, Some(1));
+
+    // Data without the trailing `min_balance` creates a policy without a threshold.
+    let policy = TestPolicy::try_create(&mut context, |create_policy| {
+        let instruction = legacy(create_policy.instruction());
+        assert_eq!(instruction.data, vec![0, PermissionStrategy::Deny as u8]);
+        instruction
+    })
+    .await
+    .unwrap();
+    let (_, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(decoded.min_balance, Some(0));
+    assert_eq!(decoded.revision, Some(0));
+}
+
+#[tokio::test]
+async fn test_close_policy_with_foreign_mint() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+    let other = TestPolicy::create(&mut context).await;
+
+    // The payer owns the token of `other`, but it does not grant authority over `policy`.
+    let foreign = TestPolicy {
+        mint: other.mint,
+        token_account: other.token_account,
+        address: policy.address,
+    };
+    let result = send(&mut context, foreign.close(&context), 0).await;
+    assert!(result.is_err());
+
+    let account = context
+        .banks_client
+        .get_account(policy.address)
+        .await
+        .unwrap();
+    assert!(account.is_some());
+
+    send(&mut context, policy.close(&context), 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_lock_policy() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    send(&mut context, policy.add(&context, first), 0)
+        .await
+        .unwrap();
+    send(&mut context, policy.lock(&context), 1).await.unwrap();
+
+    let (_, decoded) = policy.fetch(&mut context).await;
+    assert!(decoded.locked);
+    assert_eq!(decoded.revision, Some(2));
+
+    let rejected = [
+        policy.add(&context, second),
+        policy.remove(&context, 0),
+        policy.replace(&context, 0, second),
+        policy.remove_by_key(&context, first),
+        policy.replace_by_key(&context, first, second),
+        policy.set_min_balance(&context, 1),
+        policy.lock(&context),
+        policy.close(&context),
+    ];
+    for (nonce, instruction) in rejected.into_iter().enumerate() {
+        let result = send(&mut context, instruction, 2 + nonce as u64).await;
+        assert_shield_error(result, ShieldError::Immutable);
+    }
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 1);
+    assert_eq!(decoded.revision, Some(2));
+    assert_eq!(decoded.identities, vec![first]);
+}
+
+#[tokio::test]
+async fn test_events() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let event = |kind, identity, index, identities_len, revision| PolicyEvent {
+        kind,
+        policy: policy.address,
+        identity,
+        previous_identity: Pubkey::default(),
+        index,
+        identities_len,
+        revision,
+    };
+
+    let events = send_with_events(&mut context, policy.add(&context, first), 0).await;
+    assert_eq!(
+        events,
+        vec![event(PolicyEventKind::IdentityAdded, first, 0, 1, 1)]
+    );
+
+    let events = send_with_events(
+        &mut context,
+        policy.replace_by_key(&context, first, second),
+        1,
+    )
+    .await;
+    assert_eq!(
+        events,
+        vec![PolicyEvent {
+            previous_identity: first,
+            ..event(PolicyEventKind::IdentityReplaced, second, 0, 1, 2)
+        }]
+    );
+
+    let events = send_with_events(&mut context, policy.replace(&context, 0, first), 2).await;
+    assert_eq!(
+        events,
+        vec![PolicyEvent {
+            previous_identity: second,
+            ..event(PolicyEventKind::IdentityReplaced, first, 0, 1, 3)
+        }]
+    );
+
+    let events = send_with_events(&mut context, policy.remove(&context, 0), 3).await;
+    assert_eq!(
+        events,
+        vec![event(PolicyEventKind::IdentityRemoved, first, 0, 0, 4)]
+    );
+
+    let events = send_with_events(&mut context, policy.lock(&context), 4).await;
+    assert_eq!(
+        events,
+        vec![event(
+            PolicyEventKind::PolicyLocked,
+            Pubkey::default(),
+            0,
+            0,
+            5
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_policy() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    // Rewrite the policy as a legacy `Policy` account whose `identities_len` is off by one.
+    let account = context
+        .banks_client
+        .get_account(policy.address)
+        .await
+        .unwrap()
+        .unwrap();
+    let nonce = account.data[2];
+
+    let mut data = vec![Kind::Policy as u8, PermissionStrategy::Deny as u8, nonce];
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(first.as_ref());
+    data.extend_from_slice(Pubkey::default().as_ref());
+    data.extend_from_slice(second.as_ref());
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let legacy_account = |data: Vec<u8>| {
+        AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(data.len()),
+            data,
+            owner: yellowstone_shield_client::ID,
+            executable: false,
+            rent_epoch: 0,
+        })
+    };
+
+    // A buffer ending with a partial identity is rejected instead of truncated.
+    let mut malformed = data.clone();
+    malformed.extend_from_slice(&[1; 5]);
+    context.set_account(&policy.address, &legacy_account(malformed));
+
+    let result = send(&mut context, policy.migrate(&context), 0).await;
+    assert_shield_error(result, ShieldError::InvalidAccountData);
+
+    context.set_account(&policy.address, &legacy_account(data));
+
+    send(&mut context, policy.migrate(&context), 1)
+        .await
+        .unwrap();
+
+    let (identities_len, decoded) = policy.fetch(&mut context).await;
+    assert_eq!(identities_len, 2);
+    assert_eq!(decoded.kind, Kind::PolicyV3);
+    assert_eq!(decoded.strategy, PermissionStrategy::Deny);
+    assert_eq!(decoded.mint, Some(policy.mint));
+    assert_eq!(decoded.revision, Some(0));
+    assert_eq!(decoded.identities, vec![first, Pubkey::default(), second]);
+
+    let result = send(&mut context, policy.migrate(&context), 2).await;
+    assert_shield_error(result, ShieldError::PolicyAlreadyMigrated);
+}
+
+#[tokio::test]
+async fn test_identities_len_matches_buffer() {
+    let mut context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
+        .start_with_context()
+        .await;
+    let policy = TestPolicy::create(&mut context).await;
+
+    let seed = 0x5eed_u64;
+    let mut rng = XorShift(seed);
+    let mut pool: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+    pool.push(Pubkey::default());
+
+    // Model of the identities buffer, checked against the account after every operation.
+    let mut model: Vec<Pubkey> = Vec::new();
+
+    for step in 0..OPERATIONS {
+        let identity = pool[rng.below(pool.len())];
+        let present: HashSet<Pubkey> = model.iter().copied().collect();
+
+        let (instruction, expected) = match rng.below(3) {
+            0 => {
+                let expected = if identity == Pubkey::default() {
+                    Err(ShieldError::InvalidIdentity)
+                } else if present.contains(&identity) {
+                    Err(ShieldError::IdentityAlreadyExists)
+                } else {
+                    Ok(())
+                };
+                (policy.add(&context, identity), expected)
+            }
+            1 => {
+                let index = rng.below(model.len() + 1);
+                let expected = match model.get(index) {
+                    None => Err(ShieldError::InvalidIndexToReferenceIdentity),
+                    Some(slot) if *slot == Pubkey::default() => Err(ShieldError::IdentitySlotEmpty),
+                    Some(_) => Ok(()),
+                };
+                (policy.remove(&context, index), expected)
+            }
+            _ => {
+                let index = rng.below(model.len() + 1);
+                let expected = if identity == Pubkey::default() {
+                    Err(ShieldError::InvalidIdentity)
+                } else if index >= model.len() {
+                    Err(ShieldError::InvalidIndexToReferenceIdentity)
+                } else if present.contains(&identity) {
+                    Err(ShieldError::IdentityAlreadyExists)
+                } else {
+                    Ok(())
+                };
+                (policy.replace(&context, index, identity), expected)
+            }
+        };
+
+        let result = send(&mut context, instruction, step as u64).await;
+
+        match expected {
+            Ok(()) => {
+                result.unwrap_or_else(|e| panic!("seed {seed:#x}, step {step}: {e}"));
+
+                let (identities_len, decoded) = policy.fetch(&mut context).await;
+                model = decoded.identities.clone();
+
+                let active: Vec<&Pubkey> =
+                    model.iter().filter(|p| **p != Pubkey::default()).collect();
+                let unique: HashSet<&&Pubkey> = active.iter().collect();
+
+                assert_eq!(
+                    identities_len as usize,
+                    active.len(),
+                    "seed {seed:#x}, step {step}"
+                );
+                assert_eq!(unique.len(), active.len(), "seed {seed:#x}, step {step}");
+            }
+            Err(error) => assert_shield_error(result, error),
+        }
+    }
+}
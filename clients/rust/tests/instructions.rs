@@ -18,7 +18,7 @@ use spl_token_metadata_interface::{
 use yellowstone_shield_client::instructions::{ClosePolicyBuilder, ReplaceIdentityBuilder};
 use yellowstone_shield_client::types::{Kind, PermissionStrategy};
 use yellowstone_shield_client::{
-    accounts::PolicyV2,
+    accounts::PolicyV3,
     instructions::{AddIdentityBuilder, CreatePolicyBuilder, RemoveIdentityBuilder},
     CreateAccountBuilder, CreateAsscoiatedTokenAccountBuilder, InitializeMetadataBuilder,
     InitializeMint2Builder, MetadataPointerInitializeBuilder, PolicyTrait,
@@ -26,7 +26,7 @@ use yellowstone_shield_client::{
 };
 
 #[tokio::test]
-async fn test_policy_v2_lifecycle() {
+async fn test_policy_v3_lifecycle() {
     let context = ProgramTest::new("yellowstone_shield", yellowstone_shield_client::ID, None)
         .start_with_context()
         .await;
@@ -86,8 +86,8 @@ async fn test_policy_v2_lifecycle() {
         .instruction();
 
     // Create the policy account.
-    // PDA seeds are same for both Policy and PolicyV2
-    let address = PolicyV2::find_pda(&mint.pubkey()).0;
+    // PDA seeds are same for both Policy and PolicyV3
+    let address = PolicyV3::find_pda(&mint.pubkey()).0;
     let create_policy_ix = CreatePolicyBuilder::new()
         .policy(address)
         .mint(mint.pubkey())
@@ -134,10 +134,10 @@ async fn test_policy_v2_lifecycle() {
     let policy_account = policy_account.unwrap();
     let mut policy_account_data = policy_account.data.as_ref();
 
-    let policy = PolicyV2::deserialize(&mut policy_account_data).unwrap();
+    let policy = PolicyV3::deserialize(&mut policy_account_data).unwrap();
 
-    assert_eq!(policy_account.data.len(), PolicyV2::LEN);
-    assert_eq!(policy.try_kind().unwrap(), Kind::PolicyV2);
+    assert_eq!(policy_account.data.len(), PolicyV3::LEN);
+    assert_eq!(policy.try_kind().unwrap(), Kind::PolicyV3);
     assert_eq!(policy.try_strategy().unwrap(), PermissionStrategy::Allow);
     assert_eq!(policy.current_identities_len(), 0);
 
@@ -195,10 +195,10 @@ async fn test_policy_v2_lifecycle() {
 
     let policy_account = policy_account.unwrap();
     let policy_account_data = policy_account.data;
-    let policy = PolicyV2::deserialize(&mut &policy_account_data[..PolicyV2::LEN]).unwrap();
+    let policy = PolicyV3::deserialize(&mut &policy_account_data[..PolicyV3::LEN]).unwrap();
 
     assert_eq!(policy.current_identities_len(), 2);
-    let identites = &policy_account_data[PolicyV2::LEN..];
+    let identites = &policy_account_data[PolicyV3::LEN..];
 
     let first_bytes = first.to_bytes();
     let second_bytes = second.to_bytes();
@@ -234,10 +234,10 @@ async fn test_policy_v2_lifecycle() {
     let policy_account = policy_account.unwrap();
     let policy_account_data = policy_account.data;
 
-    let policy = PolicyV2::deserialize(&mut &policy_account_data[..PolicyV2::LEN]).unwrap();
+    let policy = PolicyV3::deserialize(&mut &policy_account_data[..PolicyV3::LEN]).unwrap();
 
     assert_eq!(policy.current_identities_len(), 1);
-    let identites = &policy_account_data[PolicyV2::LEN..];
+    let identites = &policy_account_data[PolicyV3::LEN..];
 
     let zeroed_bytes = [0u8; 32];
     let second_bytes = second.to_bytes();
@@ -272,10 +272,10 @@ async fn test_policy_v2_lifecycle() {
 
     let policy_account = policy_account.unwrap();
     let policy_account_data = policy_account.data;
-    let policy = PolicyV2::deserialize(&mut &policy_account_data[..PolicyV2::LEN]).unwrap();
+    let policy = PolicyV3::deserialize(&mut &policy_account_data[..PolicyV3::LEN]).unwrap();
 
     assert_eq!(policy.current_identities_len(), 2);
-    let identites = &policy_account_data[PolicyV2::LEN..];
+    let identites = &policy_account_data[PolicyV3::LEN..];
 
     assert_eq!(identites, &first_second_identities);
 
@@ -3,10 +3,14 @@ use yellowstone_shield_client::ID;
 use yellowstone_shield_client::{accounts, types::PermissionStrategy, PolicyTrait};
 use yellowstone_vixen_core::AccountUpdate;
 
+/// A decoded policy account, built with `Policy::new` outside this crate.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Policy {
     pub strategy: PermissionStrategy,
     pub identities: Vec<Pubkey>,
+    /// Whether the policy was locked, only `PolicyV3` accounts can be locked.
+    pub locked: bool,
 }
 
 impl Policy {
@@ -14,8 +18,14 @@ impl Policy {
         Self {
             strategy,
             identities,
+            locked: false,
         }
     }
+
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
 }
 
 /// Shield Program State
@@ -28,12 +38,13 @@ pub enum ShieldProgramState {
 impl ShieldProgramState {
     fn parse_policy<T: PolicyTrait>(data: &[u8]) -> yellowstone_vixen_core::ParseResult<Policy> {
         let policy = T::from_bytes(data)?;
-        let identities = accounts::Policy::try_deserialize_identities(data)?;
+        let identities = T::try_deserialize_identities(data)?;
         let strategy = policy.try_strategy()?;
 
         Ok(Policy {
             strategy,
             identities,
+            locked: policy.is_locked(),
         })
     }
 
@@ -53,6 +64,7 @@ impl ShieldProgramState {
         let policy = match data[0] {
             0 => Self::parse_policy::<accounts::Policy>(data)?,
             1 => Self::parse_policy::<accounts::PolicyV2>(data)?,
+            2 => Self::parse_policy::<accounts::PolicyV3>(data)?,
             _ => {
                 return Err(yellowstone_vixen_core::ParseError::from(
                     "Unsupported data type".to_owned(),
@@ -0,0 +1,212 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use yellowstone_shield_client::{PolicyEvent, ID};
+use yellowstone_vixen_core::TransactionUpdate;
+
+/// The policy events logged by the Shield program in a transaction.
+#[derive(Debug, Clone)]
+pub struct ShieldProgramEvents {
+    pub slot: u64,
+    pub signature: Vec<u8>,
+    /// Events in the order they were logged, inner instructions included.
+    pub events: Vec<PolicyEvent>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct EventParser;
+
+impl EventParser {
+    /// Decodes the `Program data:` entries logged while the Shield program was executing.
+    ///
+    /// Invocations are tracked from the `invoke` and `success`/`failed` lines so data logged
+    /// by other programs, including programs invoked by Shield, is ignored.
+    pub fn parse_logs<S: AsRef<str>>(
+        logs: &[S],
+    ) -> yellowstone_vixen_core::ParseResult<Vec<PolicyEvent>> {
+        let program_id = ID.to_string();
+        let mut invocations: Vec<&str> = Vec::new();
+        let mut events = Vec::new();
+
+        for log in logs {
+            let log = log.as_ref();
+
+            if let Some(data) = log.strip_prefix("Program data: ") {
+                if invocations.last() != Some(&program_id.as_str()) {
+                    continue;
+                }
+
+                for field in data.split_whitespace() {
+                    let bytes = STANDARD.decode(field).map_err(|e| {
+                        yellowstone_vixen_core::ParseError::from(format!(
+                            "Invalid event encoding: {e}"
+                        ))
+                    })?;
+                    let event = PolicyEvent::try_from_bytes(&bytes).map_err(|e| {
+                        yellowstone_vixen_core::ParseError::from(format!("Invalid event: {e}"))
+                    })?;
+
+                    events.push(event);
+                }
+
+                continue;
+            }
+
+            let Some(rest) = log.strip_prefix("Program ") else {
+                continue;
+            };
+            let mut parts = rest.split_whitespace();
+
+            match (parts.next(), parts.next()) {
+                (Some(program), Some("invoke")) => invocations.push(program),
+                (Some(_), Some("success" | "failed:")) => {
+                    invocations.pop();
+                }
+                _ => {}
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+impl yellowstone_vixen_core::Parser for EventParser {
+    type Input = TransactionUpdate;
+    type Output = ShieldProgramEvents;
+
+    fn id(&self) -> std::borrow::Cow<str> {
+        "Shield::EventParser".into()
+    }
+
+    fn prefilter(&self) -> yellowstone_vixen_core::Prefilter {
+        yellowstone_vixen_core::Prefilter::builder()
+            .transaction_accounts([ID])
+            .build()
+            .unwrap()
+    }
+
+    async fn parse(
+        &self,
+        txn: &TransactionUpdate,
+    ) -> yellowstone_vixen_core::ParseResult<Self::Output> {
+        let Some(info) = txn.transaction.as_ref() else {
+            return Err(yellowstone_vixen_core::ParseError::Filtered);
+        };
+        let Some(meta) = info.meta.as_ref() else {
+            return Err(yellowstone_vixen_core::ParseError::Filtered);
+        };
+
+        // Mutations of failed transactions are rolled back.
+        if meta.err.is_some() {
+            return Err(yellowstone_vixen_core::ParseError::Filtered);
+        }
+
+        let events = EventParser::parse_logs(&meta.log_messages)?;
+
+        if events.is_empty() {
+            return Err(yellowstone_vixen_core::ParseError::Filtered);
+        }
+
+        Ok(ShieldProgramEvents {
+            slot: txn.slot,
+            signature: info.signature.clone(),
+            events,
+        })
+    }
+}
+
+impl yellowstone_vixen_core::ProgramParser for EventParser {
+    #[inline]
+    fn program_id(&self) -> yellowstone_vixen_core::Pubkey {
+        ID.to_bytes().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::pubkey::Pubkey;
+    use yellowstone_shield_client::{PolicyEventKind, EVENT_VERSION};
+
+    use super::*;
+
+    fn program_data(event: &PolicyEvent) -> String {
+        format!("Program data: {}", STANDARD.encode(event.to_bytes()))
+    }
+
+    #[test]
+    fn test_parse_logs() {
+        let added = PolicyEvent {
+            kind: PolicyEventKind::IdentityAdded,
+            policy: Pubkey::new_unique(),
+            identity: Pubkey::new_unique(),
+            previous_identity: Pubkey::default(),
+            index: 3,
+            identities_len: 4,
+            revision: 7,
+        };
+        let locked = PolicyEvent {
+            kind: PolicyEventKind::PolicyLocked,
+            policy: added.policy,
+            identity: Pubkey::default(),
+            previous_identity: Pubkey::default(),
+            index: 0,
+            identities_len: 4,
+            revision: 9,
+        };
+        let replaced = PolicyEvent {
+            kind: PolicyEventKind::IdentityReplaced,
+            policy: added.policy,
+            identity: Pubkey::new_unique(),
+            previous_identity: added.identity,
+            index: 3,
+            identities_len: 4,
+            revision: 8,
+        };
+        let other = Pubkey::new_unique();
+
+        let logs = vec![
+            format!("Program {} invoke [1]", ID),
+            "Program log: Instruction: Add Identity".to_string(),
+            format!("Program {} invoke [2]", other),
+            // Data logged by an inner program is not a Shield event.
+            "Program data: AAEC".to_string(),
+            format!("Program {} success", other),
+            program_data(&added),
+            program_data(&replaced),
+            format!("Program {} consumed 5000 of 200000 compute units", ID),
+            format!("Program {} success", ID),
+            format!("Program {} invoke [1]", other),
+            "Program data: AAEC".to_string(),
+            format!("Program {} success", other),
+            format!("Program {} invoke [1]", ID),
+            program_data(&locked),
+            format!("Program {} success", ID),
+        ];
+
+        assert_eq!(
+            EventParser::parse_logs(&logs).unwrap(),
+            vec![added, replaced, locked]
+        );
+    }
+
+    #[test]
+    fn test_parse_logs_unsupported_version() {
+        let mut data = PolicyEvent {
+            kind: PolicyEventKind::PolicyClosed,
+            policy: Pubkey::new_unique(),
+            identity: Pubkey::default(),
+            previous_identity: Pubkey::default(),
+            index: 0,
+            identities_len: 0,
+            revision: 1,
+        }
+        .to_bytes();
+        data[0] = EVENT_VERSION + 1;
+
+        let logs = vec![
+            format!("Program {} invoke [1]", ID),
+            format!("Program data: {}", STANDARD.encode(data)),
+            format!("Program {} success", ID),
+        ];
+
+        assert!(EventParser::parse_logs(&logs).is_err());
+    }
+}
@@ -8,9 +8,18 @@
 use borsh::BorshDeserialize;
 use yellowstone_shield_client::instructions::{
     AddIdentity as AddIdentityIxAccounts, AddIdentityInstructionArgs as AddIdentityIxData,
+    ClosePolicy as ClosePolicyIxAccounts, ClosePolicyInstructionArgs as ClosePolicyIxData,
     CreatePolicy as CreatePolicyIxAccounts, CreatePolicyInstructionArgs as CreatePolicyIxData,
-    RemoveIdentity as RemoveIdentityIxAccounts,
+    LockPolicy as LockPolicyIxAccounts, LockPolicyInstructionArgs as LockPolicyIxData,
+    MigratePolicy as MigratePolicyIxAccounts, RemoveIdentity as RemoveIdentityIxAccounts,
+    RemoveIdentityByKey as RemoveIdentityByKeyIxAccounts,
+    RemoveIdentityByKeyInstructionArgs as RemoveIdentityByKeyIxData,
     RemoveIdentityInstructionArgs as RemoveIdentityIxData,
+    ReplaceIdentity as ReplaceIdentityIxAccounts,
+    ReplaceIdentityByKey as ReplaceIdentityByKeyIxAccounts,
+    ReplaceIdentityByKeyInstructionArgs as ReplaceIdentityByKeyIxData,
+    ReplaceIdentityInstructionArgs as ReplaceIdentityIxData,
+    SetMinBalance as SetMinBalanceIxAccounts, SetMinBalanceInstructionArgs as SetMinBalanceIxData,
 };
 use yellowstone_shield_client::ID;
 
@@ -21,6 +30,13 @@ pub enum ShieldProgramIx {
     CreatePolicy(CreatePolicyIxAccounts, CreatePolicyIxData),
     AddIdentity(AddIdentityIxAccounts, AddIdentityIxData),
     RemoveIdentity(RemoveIdentityIxAccounts, RemoveIdentityIxData),
+    ReplaceIdentity(ReplaceIdentityIxAccounts, ReplaceIdentityIxData),
+    ClosePolicy(ClosePolicyIxAccounts, ClosePolicyIxData),
+    RemoveIdentityByKey(RemoveIdentityByKeyIxAccounts, RemoveIdentityByKeyIxData),
+    ReplaceIdentityByKey(ReplaceIdentityByKeyIxAccounts, ReplaceIdentityByKeyIxData),
+    MigratePolicy(MigratePolicyIxAccounts),
+    SetMinBalance(SetMinBalanceIxAccounts, SetMinBalanceIxData),
+    LockPolicy(LockPolicyIxAccounts, LockPolicyIxData),
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -65,9 +81,17 @@ impl InstructionParser {
         ix: &yellowstone_vixen_core::instruction::InstructionUpdate,
     ) -> yellowstone_vixen_core::ParseResult<ShieldProgramIx> {
         let accounts_len = ix.accounts.len();
-        let ix_discriminator: [u8; 1] = ix.data[0..1].try_into()?;
-        let mut ix_data = &ix.data[1..];
-        match ix_discriminator {
+        let (ix_discriminator, mut ix_data) = ix.data.split_first().ok_or_else(|| {
+            yellowstone_vixen_core::ParseError::from("Instruction data is empty".to_owned())
+        })?;
+        // Like `ShieldInstruction::unpack`, accept `CreatePolicy` to `ClosePolicy` data without
+        // their trailing `Option` argument. Trailing bytes are ignored, so a `None` is appended.
+        let legacy_data;
+        if *ix_discriminator <= 4 {
+            legacy_data = [ix_data, &[0][..]].concat();
+            ix_data = &legacy_data;
+        }
+        match [*ix_discriminator] {
             [0] => {
                 check_min_accounts_req(accounts_len, 6)?;
                 let ix_accounts = CreatePolicyIxAccounts {
@@ -82,7 +106,7 @@ impl InstructionParser {
                 Ok(ShieldProgramIx::CreatePolicy(ix_accounts, de_ix_data))
             }
             [1] => {
-                check_min_accounts_req(accounts_len, 5)?;
+                check_min_accounts_req(accounts_len, 6)?;
                 let ix_accounts = AddIdentityIxAccounts {
                     mint: ix.accounts[0].0.into(),
                     token_account: ix.accounts[1].0.into(),
@@ -95,16 +119,105 @@ impl InstructionParser {
                 Ok(ShieldProgramIx::AddIdentity(ix_accounts, de_ix_data))
             }
             [2] => {
-                check_min_accounts_req(accounts_len, 5)?;
+                check_min_accounts_req(accounts_len, 4)?;
                 let ix_accounts = RemoveIdentityIxAccounts {
                     mint: ix.accounts[0].0.into(),
                     token_account: ix.accounts[1].0.into(),
                     policy: ix.accounts[2].0.into(),
-                    owner: ix.accounts[4].0.into(),
+                    owner: ix.accounts[3].0.into(),
                 };
                 let de_ix_data: RemoveIdentityIxData = BorshDeserialize::deserialize(&mut ix_data)?;
                 Ok(ShieldProgramIx::RemoveIdentity(ix_accounts, de_ix_data))
             }
+            [3] => {
+                check_min_accounts_req(accounts_len, 4)?;
+                let ix_accounts = ReplaceIdentityIxAccounts {
+                    mint: ix.accounts[0].0.into(),
+                    token_account: ix.accounts[1].0.into(),
+                    policy: ix.accounts[2].0.into(),
+                    owner: ix.accounts[3].0.into(),
+                };
+                let de_ix_data: ReplaceIdentityIxData =
+                    BorshDeserialize::deserialize(&mut ix_data)?;
+                Ok(ShieldProgramIx::ReplaceIdentity(ix_accounts, de_ix_data))
+            }
+            [4] => {
+                check_min_accounts_req(accounts_len, 6)?;
+                let ix_accounts = ClosePolicyIxAccounts {
+                    mint: ix.accounts[0].0.into(),
+                    token_account: ix.accounts[1].0.into(),
+                    policy: ix.accounts[2].0.into(),
+                    payer: ix.accounts[3].0.into(),
+                    owner: ix.accounts[4].0.into(),
+                    system_program: ix.accounts[5].0.into(),
+                };
+                let de_ix_data: ClosePolicyIxData = BorshDeserialize::deserialize(&mut ix_data)?;
+                Ok(ShieldProgramIx::ClosePolicy(ix_accounts, de_ix_data))
+            }
+            [5] => {
+                check_min_accounts_req(accounts_len, 4)?;
+                let ix_accounts = RemoveIdentityByKeyIxAccounts {
+                    mint: ix.accounts[0].0.into(),
+                    token_account: ix.accounts[1].0.into(),
+                    policy: ix.accounts[2].0.into(),
+                    owner: ix.accounts[3].0.into(),
+                };
+                let de_ix_data: RemoveIdentityByKeyIxData =
+                    BorshDeserialize::deserialize(&mut ix_data)?;
+                Ok(ShieldProgramIx::RemoveIdentityByKey(
+                    ix_accounts,
+                    de_ix_data,
+                ))
+            }
+            [6] => {
+                check_min_accounts_req(accounts_len, 4)?;
+                let ix_accounts = ReplaceIdentityByKeyIxAccounts {
+                    mint: ix.accounts[0].0.into(),
+                    token_account: ix.accounts[1].0.into(),
+                    policy: ix.accounts[2].0.into(),
+                    owner: ix.accounts[3].0.into(),
+                };
+                let de_ix_data: ReplaceIdentityByKeyIxData =
+                    BorshDeserialize::deserialize(&mut ix_data)?;
+                Ok(ShieldProgramIx::ReplaceIdentityByKey(
+                    ix_accounts,
+                    de_ix_data,
+                ))
+            }
+            [7] => {
+                check_min_accounts_req(accounts_len, 6)?;
+                let ix_accounts = MigratePolicyIxAccounts {
+                    mint: ix.accounts[0].0.into(),
+                    token_account: ix.accounts[1].0.into(),
+                    policy: ix.accounts[2].0.into(),
+                    payer: ix.accounts[3].0.into(),
+                    owner: ix.accounts[4].0.into(),
+                    system_program: ix.accounts[5].0.into(),
+                };
+                Ok(ShieldProgramIx::MigratePolicy(ix_accounts))
+            }
+            [8] => {
+                check_min_accounts_req(accounts_len, 4)?;
+                let ix_accounts = SetMinBalanceIxAccounts {
+                    mint: ix.accounts[0].0.into(),
+                    token_account: ix.accounts[1].0.into(),
+                    policy: ix.accounts[2].0.into(),
+                    owner: ix.accounts[3].0.into(),
+                };
+                let de_ix_data: SetMinBalanceIxData = BorshDeserialize::deserialize(&mut ix_data)?;
+                Ok(ShieldProgramIx::SetMinBalance(ix_accounts, de_ix_data))
+            }
+            [9] => {
+                check_min_accounts_req(accounts_len, 4)?;
+                let ix_accounts = LockPolicyIxAccounts {
+                    mint: ix.accounts[0].0.into(),
+                    token_account: ix.accounts[1].0.into(),
+                    policy: ix.accounts[2].0.into(),
+                    owner: ix.accounts[3].0.into(),
+                };
+                let de_ix_data: LockPolicyIxData = BorshDeserialize::deserialize(&mut ix_data)?;
+                Ok(ShieldProgramIx::LockPolicy(ix_accounts, de_ix_data))
+            }
             _ => Err(yellowstone_vixen_core::ParseError::from(
                 "Invalid Instruction discriminator".to_owned(),
             )),
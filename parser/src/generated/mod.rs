@@ -5,6 +5,4 @@
 //! <https://github.com/codama-idl/codama>
 //!
 
-pub mod accounts_parser;
-
 pub mod instructions_parser;
@@ -1,3 +1,8 @@
+pub mod accounts_parser;
+pub mod events_parser;
 mod generated;
 
 pub use generated::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,424 @@
+use std::sync::Arc;
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use yellowstone_shield_client::{
+    instructions::{
+        AddIdentityBuilder, ClosePolicyBuilder, CreatePolicyBuilder, LockPolicyBuilder,
+        MigratePolicyBuilder, RemoveIdentityBuilder, RemoveIdentityByKeyBuilder,
+        ReplaceIdentityBuilder, ReplaceIdentityByKeyBuilder, SetMinBalanceBuilder,
+    },
+    types::PermissionStrategy,
+};
+use yellowstone_vixen_core::instruction::InstructionUpdate;
+
+use crate::instructions_parser::{InstructionParser, ShieldProgramIx};
+
+fn to_update(ix: Instruction) -> InstructionUpdate {
+    InstructionUpdate {
+        program: ix.program_id.to_bytes().into(),
+        accounts: ix
+            .accounts
+            .iter()
+            .map(|meta| meta.pubkey.to_bytes().into())
+            .collect(),
+        data: ix.data,
+        shared: Arc::default(),
+        inner: Vec::new(),
+    }
+}
+
+#[test]
+fn test_parse_create_policy() {
+    let (mint, token_account, policy, payer, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = CreatePolicyBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .payer(payer)
+        .owner(owner)
+        .strategy(PermissionStrategy::Allow)
+        .min_balance(51)
+        .instruction();
+
+    let ShieldProgramIx::CreatePolicy(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected CreatePolicy");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.payer, payer);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(accounts.system_program, solana_program::system_program::ID);
+    assert_eq!(data.strategy, PermissionStrategy::Allow);
+    assert_eq!(data.min_balance, Some(51));
+}
+
+#[test]
+fn test_parse_add_identity() {
+    let (mint, token_account, policy, payer, owner, identity) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = AddIdentityBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .payer(payer)
+        .owner(owner)
+        .identity(identity)
+        .instruction();
+
+    let ShieldProgramIx::AddIdentity(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected AddIdentity");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.payer, payer);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(accounts.system_program, solana_program::system_program::ID);
+    assert_eq!(data.identity, identity);
+    assert_eq!(data.expected_revision, None);
+}
+
+#[test]
+fn test_parse_remove_identity() {
+    let (mint, token_account, policy, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = RemoveIdentityBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .owner(owner)
+        .index(7)
+        .instruction();
+
+    let ShieldProgramIx::RemoveIdentity(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected RemoveIdentity");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(data.index, 7);
+}
+
+#[test]
+fn test_parse_replace_identity() {
+    let (mint, token_account, policy, owner, identity) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = ReplaceIdentityBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .owner(owner)
+        .index(3)
+        .identity(identity)
+        .instruction();
+
+    let ShieldProgramIx::ReplaceIdentity(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected ReplaceIdentity");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(data.index, 3);
+    assert_eq!(data.identity, identity);
+}
+
+#[test]
+fn test_parse_close_policy() {
+    let (mint, token_account, policy, payer, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = ClosePolicyBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .payer(payer)
+        .owner(owner)
+        .expected_revision(5)
+        .instruction();
+
+    let ShieldProgramIx::ClosePolicy(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected ClosePolicy");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.payer, payer);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(accounts.system_program, solana_program::system_program::ID);
+    assert_eq!(data.expected_revision, Some(5));
+}
+
+#[test]
+fn test_parse_remove_identity_by_key() {
+    let (mint, token_account, policy, owner, identity) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = RemoveIdentityByKeyBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .owner(owner)
+        .identity(identity)
+        .instruction();
+
+    let ShieldProgramIx::RemoveIdentityByKey(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected RemoveIdentityByKey");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(data.identity, identity);
+}
+
+#[test]
+fn test_parse_replace_identity_by_key() {
+    let (mint, token_account, policy, owner, old_identity, new_identity) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = ReplaceIdentityByKeyBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .owner(owner)
+        .old_identity(old_identity)
+        .new_identity(new_identity)
+        .instruction();
+
+    let ShieldProgramIx::ReplaceIdentityByKey(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected ReplaceIdentityByKey");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(data.old_identity, old_identity);
+    assert_eq!(data.new_identity, new_identity);
+}
+
+#[test]
+fn test_parse_migrate_policy() {
+    let (mint, token_account, policy, payer, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = MigratePolicyBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .payer(payer)
+        .owner(owner)
+        .instruction();
+
+    let ShieldProgramIx::MigratePolicy(accounts) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected MigratePolicy");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.payer, payer);
+    assert_eq!(accounts.owner, owner);
+}
+
+#[test]
+fn test_parse_set_min_balance() {
+    let (mint, token_account, policy, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = SetMinBalanceBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .owner(owner)
+        .min_balance(500)
+        .expected_revision(3)
+        .instruction();
+
+    let ShieldProgramIx::SetMinBalance(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected SetMinBalance");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(data.min_balance, 500);
+    assert_eq!(data.expected_revision, Some(3));
+}
+
+#[test]
+fn test_parse_lock_policy() {
+    let (mint, token_account, policy, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = LockPolicyBuilder::new()
+        .mint(mint)
+        .token_account(token_account)
+        .policy(policy)
+        .owner(owner)
+        .instruction();
+
+    let ShieldProgramIx::LockPolicy(accounts, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected LockPolicy");
+    };
+
+    assert_eq!(accounts.mint, mint);
+    assert_eq!(accounts.token_account, token_account);
+    assert_eq!(accounts.policy, policy);
+    assert_eq!(accounts.owner, owner);
+    assert_eq!(data.expected_revision, None);
+}
+
+#[test]
+fn test_parse_legacy_data() {
+    let mut ix = CreatePolicyBuilder::new()
+        .mint(Pubkey::new_unique())
+        .token_account(Pubkey::new_unique())
+        .policy(Pubkey::new_unique())
+        .payer(Pubkey::new_unique())
+        .owner(Pubkey::new_unique())
+        .strategy(PermissionStrategy::Deny)
+        .instruction();
+    // Data written before `CreatePolicy` gained its trailing `min_balance`.
+    ix.data = vec![0, PermissionStrategy::Deny as u8];
+
+    let ShieldProgramIx::CreatePolicy(_, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected CreatePolicy");
+    };
+    assert_eq!(data.strategy, PermissionStrategy::Deny);
+    assert_eq!(data.min_balance, None);
+
+    let identity = Pubkey::new_unique();
+    let mut ix = AddIdentityBuilder::new()
+        .mint(Pubkey::new_unique())
+        .token_account(Pubkey::new_unique())
+        .policy(Pubkey::new_unique())
+        .payer(Pubkey::new_unique())
+        .owner(Pubkey::new_unique())
+        .identity(identity)
+        .instruction();
+    // Data written before `AddIdentity` gained its trailing `expected_revision`.
+    assert_eq!(ix.data.pop(), Some(0));
+
+    let ShieldProgramIx::AddIdentity(_, data) =
+        InstructionParser::parse_impl(&to_update(ix)).unwrap()
+    else {
+        panic!("expected AddIdentity");
+    };
+    assert_eq!(data.identity, identity);
+    assert_eq!(data.expected_revision, None);
+
+    // Instructions added with the trailing argument still require it.
+    let mut ix = RemoveIdentityByKeyBuilder::new()
+        .mint(Pubkey::new_unique())
+        .token_account(Pubkey::new_unique())
+        .policy(Pubkey::new_unique())
+        .owner(Pubkey::new_unique())
+        .identity(identity)
+        .instruction();
+    ix.data.pop();
+
+    assert!(InstructionParser::parse_impl(&to_update(ix)).is_err());
+}
+
+#[test]
+fn test_parse_too_few_accounts() {
+    let mut ix = RemoveIdentityBuilder::new()
+        .mint(Pubkey::new_unique())
+        .token_account(Pubkey::new_unique())
+        .policy(Pubkey::new_unique())
+        .owner(Pubkey::new_unique())
+        .index(0)
+        .instruction();
+    ix.accounts.pop();
+
+    assert!(InstructionParser::parse_impl(&to_update(ix)).is_err());
+}
+
+#[test]
+fn test_parse_empty_data() {
+    let mut ix = LockPolicyBuilder::new()
+        .mint(Pubkey::new_unique())
+        .token_account(Pubkey::new_unique())
+        .policy(Pubkey::new_unique())
+        .owner(Pubkey::new_unique())
+        .instruction();
+    ix.data.clear();
+
+    assert!(InstructionParser::parse_impl(&to_update(ix)).is_err());
+}
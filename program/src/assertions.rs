@@ -1,4 +1,4 @@
-use crate::error::ShieldError;
+use crate::{error::ShieldError, BYTES_PER_PUBKEY};
 use pinocchio::{
     account_info::AccountInfo,
     msg,
@@ -17,6 +17,29 @@ pub fn assert_strategy(strategy: u8) -> ProgramResult {
     Ok(())
 }
 
+/// Assert that the given identity is not the default public key, which marks empty slots.
+pub fn assert_valid_identity(identity: &Pubkey) -> ProgramResult {
+    if *identity == Pubkey::default() {
+        msg!("Identity must not be the default public key");
+        return Err(ShieldError::InvalidIdentity.into());
+    }
+
+    Ok(())
+}
+
+/// Assert that the given identity is not already in the identities buffer.
+pub fn assert_identity_absent(identities: &[u8], identity: &Pubkey) -> ProgramResult {
+    if identities
+        .chunks_exact(BYTES_PER_PUBKEY)
+        .any(|existing| existing == identity)
+    {
+        msg!("Identity [{:?}] already exists in the policy", identity);
+        return Err(ShieldError::IdentityAlreadyExists.into());
+    }
+
+    Ok(())
+}
+
 /// Assert that the given account is owned by the given program.
 pub fn assert_program_owner(
     account_name: &str,
@@ -221,6 +244,25 @@ pub fn assert_positive_amount(
     }
 }
 
+/// Assert that the given token account holds at least `min_balance` tokens.
+pub fn assert_min_balance(
+    account_name: &str,
+    account: &StateWithExtensions<spl_token_2022::state::Account>,
+    min_balance: u64,
+) -> ProgramResult {
+    if account.base.amount < min_balance {
+        msg!(
+            "Account \"{}\" holds {} tokens, the policy requires {}",
+            account_name,
+            account.base.amount,
+            min_balance
+        );
+        Err(ShieldError::InsufficientBalance.into())
+    } else {
+        Ok(())
+    }
+}
+
 // Assert that the given account is owned by the given token owner.
 pub fn assert_token_owner(
     account_name: &str,
@@ -142,6 +142,27 @@ pub enum ShieldError {
     // 45 - Invalid Index To Reference Identity
     #[error("Invalid Index To Reference Identity")]
     InvalidIndexToReferenceIdentity,
+    /// 46 - Identity already exists
+    #[error("Identity already exists")]
+    IdentityAlreadyExists,
+    /// 47 - Identity slot is empty
+    #[error("Identity slot is empty")]
+    IdentitySlotEmpty,
+    /// 48 - Invalid identity
+    #[error("Invalid identity")]
+    InvalidIdentity,
+    /// 49 - Policy revision does not match the expected revision
+    #[error("Policy revision does not match the expected revision")]
+    RevisionMismatch,
+    /// 50 - Policy is already at the latest version
+    #[error("Policy is already at the latest version")]
+    PolicyAlreadyMigrated,
+    /// 51 - Token balance is below the minimum balance of the policy
+    #[error("Token balance is below the minimum balance of the policy")]
+    InsufficientBalance,
+    /// 52 - Policy must be migrated to the latest version
+    #[error("Policy must be migrated to the latest version")]
+    PolicyNotMigrated,
 }
 
 impl From<std::io::Error> for ShieldError {
@@ -0,0 +1,71 @@
+use bytemuck::{bytes_of, Pod, Zeroable};
+use pinocchio::{log::sol_log_data, pubkey::Pubkey};
+
+/// Version of the `PolicyEvent` layout, bumped whenever the layout changes.
+pub const EVENT_VERSION: u8 = 2;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug)]
+pub enum EventKind {
+    PolicyCreated,
+    IdentityAdded,
+    IdentityRemoved,
+    IdentityReplaced,
+    PolicyClosed,
+    PolicyMigrated,
+    MinBalanceSet,
+    PolicyLocked,
+}
+
+/// A mutation of a policy, logged as a single `Program data:` entry so indexers do not have
+/// to diff the account.
+///
+/// `identity` and `index` are zeroed by events that do not target an identity slot,
+/// `previous_identity` by events other than `IdentityReplaced`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PolicyEvent {
+    pub version: u8,
+    pub kind: u8,
+    pub policy: Pubkey,
+    /// The identity added, removed or written by the mutation.
+    pub identity: Pubkey,
+    /// The identity overwritten by an `IdentityReplaced` mutation, zeroed if the slot was empty.
+    pub previous_identity: Pubkey,
+    /// Slot of the identities buffer holding `identity`.
+    pub index: [u8; 4],
+    /// Number of identities of the policy after the mutation, `0` once closed.
+    pub identities_len: [u8; 4],
+    /// Revision of the policy after the mutation, `0` for kinds without a revision.
+    pub revision: [u8; 8],
+}
+
+impl PolicyEvent {
+    pub fn new(kind: EventKind, policy: &Pubkey, identities_len: u32, revision: u64) -> Self {
+        Self {
+            version: EVENT_VERSION,
+            kind: kind as u8,
+            policy: *policy,
+            identity: Pubkey::default(),
+            previous_identity: Pubkey::default(),
+            index: [0; 4],
+            identities_len: identities_len.to_le_bytes(),
+            revision: revision.to_le_bytes(),
+        }
+    }
+
+    pub fn identity(mut self, identity: &Pubkey, index: usize) -> Self {
+        self.identity = *identity;
+        self.index = (index as u32).to_le_bytes();
+        self
+    }
+
+    pub fn previous_identity(mut self, previous_identity: &Pubkey) -> Self {
+        self.previous_identity = *previous_identity;
+        self
+    }
+
+    pub fn emit(&self) {
+        sol_log_data(&[bytes_of(self)]);
+    }
+}
@@ -1,14 +1,20 @@
+use std::io::Read;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use pinocchio::pubkey::Pubkey;
 use shank::ShankInstruction;
 
-use crate::state::PermissionStrategy;
+use crate::{error::ShieldError, state::PermissionStrategy};
 
+/// Instructions mutating a policy take an optional `expected_revision`. When set, the
+/// instruction fails with `RevisionMismatch` unless the policy is at that revision, so a
+/// change computed from a stale read never overwrites a concurrent change.
 #[derive(BorshDeserialize, BorshSerialize, Clone, Debug, ShankInstruction)]
 #[rustfmt::skip]
 pub enum ShieldInstruction {
     /// Creates a shield policy account and a mint account linked to the policy.
-    /// The owner of the token extension asset has authority over the policy.
+    /// The owner of the token extension asset has authority over the policy and must hold
+    /// `min_balance` tokens.
     #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
     #[account(1, name="token_account", desc = "The authority over the policy based on token ownership of the mint")]
     #[account(2, writable, name="policy", desc = "The shield policy account")]
@@ -17,6 +23,7 @@ pub enum ShieldInstruction {
     #[account(5, name="system_program", desc = "The system program")]
     CreatePolicy {
         strategy: PermissionStrategy,
+        min_balance: Option<u64>,
     },
     /// Add a new identity to the shield policy.
     #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
@@ -27,6 +34,7 @@ pub enum ShieldInstruction {
     #[account(5, name="system_program", desc = "The system program")]
     AddIdentity {
         identity: Pubkey,
+        expected_revision: Option<u64>,
     },
     /// Remove a identity from the shield policy.
     #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
@@ -35,6 +43,7 @@ pub enum ShieldInstruction {
     #[account(3, writable, signer, name="owner", desc = "The owner of the token account")]
     RemoveIdentity {
         index: usize,
+        expected_revision: Option<u64>,
     },
     /// Replace an identity by its index for the shield policy.
     #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
@@ -44,6 +53,7 @@ pub enum ShieldInstruction {
     ReplaceIdentity {
         index: usize,
         identity: Pubkey,
+        expected_revision: Option<u64>,
     },
     /// Close the shield policy account.
     #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
@@ -52,5 +62,69 @@ pub enum ShieldInstruction {
     #[account(3, writable, signer, name="payer", desc = "The account paying for the storage fees")]
     #[account(4, writable, signer, name="owner", desc = "The owner of the token account")]
     #[account(5, name="system_program", desc = "The system program")]
-    ClosePolicy
+    ClosePolicy {
+        expected_revision: Option<u64>,
+    },
+    /// Remove an identity from the shield policy by its public key.
+    #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
+    #[account(1, name="token_account", desc = "The authority over the policy based on token ownership of the mint")]
+    #[account(2, writable, name="policy", desc = "The shield policy account")]
+    #[account(3, writable, signer, name="owner", desc = "The owner of the token account")]
+    RemoveIdentityByKey {
+        identity: Pubkey,
+        expected_revision: Option<u64>,
+    },
+    /// Replace an identity by its public key for the shield policy.
+    /// An old identity equal to the default public key fills the first empty slot.
+    #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
+    #[account(1, name="token_account", desc = "The authority over the policy based on token ownership of the mint")]
+    #[account(2, writable, name="policy", desc = "The shield policy account")]
+    #[account(3, writable, signer, name="owner", desc = "The owner of the token account")]
+    ReplaceIdentityByKey {
+        old_identity: Pubkey,
+        new_identity: Pubkey,
+        expected_revision: Option<u64>,
+    },
+    /// Upgrade a `Policy` (V1) or `PolicyV2` account to a `PolicyV3` account, skipping V2 for V1
+    /// accounts since V3 records the mint like V2 does.
+    /// The mint is recorded in the header and the identities buffer is moved after it.
+    #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
+    #[account(1, name="token_account", desc = "The authority over the policy based on token ownership of the mint")]
+    #[account(2, writable, name="policy", desc = "The shield policy account")]
+    #[account(3, writable, signer, name="payer", desc = "The account paying for the storage fees")]
+    #[account(4, writable, signer, name="owner", desc = "The owner of the token account")]
+    #[account(5, name="system_program", desc = "The system program")]
+    MigratePolicy,
+    /// Set the token balance required to mutate the shield policy, any positive balance when `0`.
+    /// The owner must hold both the current and the new minimum balance.
+    #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
+    #[account(1, name="token_account", desc = "The authority over the policy based on token ownership of the mint")]
+    #[account(2, writable, name="policy", desc = "The shield policy account")]
+    #[account(3, writable, signer, name="owner", desc = "The owner of the token account")]
+    SetMinBalance {
+        min_balance: u64,
+        expected_revision: Option<u64>,
+    },
+    /// Lock the shield policy, the identities can no longer be changed and the policy can no
+    /// longer be closed. Locking is irreversible.
+    #[account(0, name="mint", desc = "The token extensions mint account linked to the policy")]
+    #[account(1, name="token_account", desc = "The authority over the policy based on token ownership of the mint")]
+    #[account(2, writable, name="policy", desc = "The shield policy account")]
+    #[account(3, writable, signer, name="owner", desc = "The owner of the token account")]
+    LockPolicy { expected_revision: Option<u64> },
+}
+
+impl ShieldInstruction {
+    /// Decodes instruction data, accepting the layout `CreatePolicy`, `AddIdentity`,
+    /// `RemoveIdentity`, `ReplaceIdentity` and `ClosePolicy` had before they gained a trailing
+    /// `Option` argument. Data ending before that argument decodes it as `None`.
+    pub fn unpack(data: &[u8]) -> Result<Self, ShieldError> {
+        match Self::try_from_slice(data) {
+            Ok(instruction) => Ok(instruction),
+            Err(e) if matches!(data.first(), Some(0..=4)) => {
+                borsh::from_reader(&mut data.chain(&[0u8][..])).map_err(|_| e.into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
 }
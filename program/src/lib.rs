@@ -2,6 +2,7 @@ pub mod assertions;
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod processor;
 pub mod state;
@@ -1,4 +1,3 @@
-use borsh::BorshDeserialize;
 use bytemuck::bytes_of;
 use pinocchio::instruction::Signer;
 use pinocchio::memory::sol_memcpy;
@@ -6,14 +5,17 @@ use pinocchio::program_error::ProgramError;
 use pinocchio::{account_info::AccountInfo, msg, pubkey::Pubkey, seeds, ProgramResult};
 
 use crate::assertions::{
-    assert_ata, assert_empty_and_owned_by_system, assert_mint_association, assert_positive_amount,
-    assert_program_owner, assert_signer, assert_strategy, assert_token_owner,
-    find_and_validate_pda, validate_pda,
+    assert_ata, assert_empty_and_owned_by_system, assert_identity_absent, assert_min_balance,
+    assert_mint_association, assert_positive_amount, assert_program_owner, assert_signer,
+    assert_strategy, assert_token_owner, assert_valid_identity, find_and_validate_pda,
+    validate_pda,
 };
 use crate::error::ShieldError;
+use crate::event::{EventKind, PolicyEvent};
 use crate::instruction::ShieldInstruction;
 use crate::state::{
-    Kind, PermissionStrategy, Policy, PolicyV2, Size, ZeroCopyLoad, IDENTITIES_LEN_SIZE,
+    Kind, PermissionStrategy, Policy, PolicyV2, PolicyV3, Size, ZeroCopyLoad, IDENTITIES_LEN_SIZE,
+    LOCKED_SIZE, MIN_BALANCE_SIZE, REVISION_SIZE,
 };
 use crate::system::{close_account, create_account, realloc_account};
 use crate::BYTES_PER_PUBKEY;
@@ -23,41 +25,91 @@ pub fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let instruction =
-        ShieldInstruction::try_from_slice(instruction_data).map_err(Into::<ShieldError>::into)?;
+    let instruction = ShieldInstruction::unpack(instruction_data)?;
 
     match instruction {
-        ShieldInstruction::CreatePolicy { strategy } => {
+        ShieldInstruction::CreatePolicy {
+            strategy,
+            min_balance,
+        } => {
             msg!("Instruction: Create Policy");
-            create_policy(accounts, strategy)
+            create_policy(accounts, strategy, min_balance)
         }
-        ShieldInstruction::AddIdentity { identity } => {
+        ShieldInstruction::AddIdentity {
+            identity,
+            expected_revision,
+        } => {
             msg!("Instruction: Add Identity");
-            add_identity(accounts, identity)
+            add_identity(accounts, identity, expected_revision)
         }
-        ShieldInstruction::RemoveIdentity { index } => {
+        ShieldInstruction::RemoveIdentity {
+            index,
+            expected_revision,
+        } => {
             msg!("Instruction: Remove Identity");
-            remove_identity(accounts, index)
+            remove_identity(accounts, index, expected_revision)
         }
-        ShieldInstruction::ReplaceIdentity { identity, index } => {
+        ShieldInstruction::ReplaceIdentity {
+            identity,
+            index,
+            expected_revision,
+        } => {
             msg!("Instruction: Replace Identity");
-            replace_identity(accounts, index, identity)
+            replace_identity(accounts, index, identity, expected_revision)
         }
-        ShieldInstruction::ClosePolicy => {
+        ShieldInstruction::ClosePolicy { expected_revision } => {
             msg!("Instruction: Close Policy");
-            close_policy(accounts)
+            close_policy(accounts, expected_revision)
+        }
+        ShieldInstruction::RemoveIdentityByKey {
+            identity,
+            expected_revision,
+        } => {
+            msg!("Instruction: Remove Identity By Key");
+            remove_identity_by_key(accounts, identity, expected_revision)
+        }
+        ShieldInstruction::ReplaceIdentityByKey {
+            old_identity,
+            new_identity,
+            expected_revision,
+        } => {
+            msg!("Instruction: Replace Identity By Key");
+            replace_identity_by_key(accounts, old_identity, new_identity, expected_revision)
+        }
+        ShieldInstruction::MigratePolicy => {
+            msg!("Instruction: Migrate Policy");
+            migrate_policy(accounts)
+        }
+        ShieldInstruction::SetMinBalance {
+            min_balance,
+            expected_revision,
+        } => {
+            msg!("Instruction: Set Min Balance");
+            set_min_balance(accounts, min_balance, expected_revision)
+        }
+        ShieldInstruction::LockPolicy { expected_revision } => {
+            msg!("Instruction: Lock Policy");
+            lock_policy(accounts, expected_revision)
         }
     }
 }
 
-fn create_policy(accounts: &[AccountInfo], strategy: PermissionStrategy) -> ProgramResult {
+/// Creates a `PolicyV3` account. The creator only needs a positive balance, the minimum
+/// balance applies to the following mutations.
+fn create_policy(
+    accounts: &[AccountInfo],
+    strategy: PermissionStrategy,
+    min_balance: Option<u64>,
+) -> ProgramResult {
     let [mint, token_account, policy, payer, owner, _system_program, ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     assert_empty_and_owned_by_system("policy", policy)?;
 
-    validate_policy_associated_accounts(owner, mint, token_account)?;
+    // A minimum balance the creator does not hold would leave the policy immutable.
+    let min_balance = min_balance.unwrap_or_default();
+    validate_policy_associated_accounts(owner, mint, token_account, min_balance)?;
 
     let strategy = strategy as u8;
     assert_strategy(strategy)?;
@@ -69,76 +121,61 @@ fn create_policy(accounts: &[AccountInfo], strategy: PermissionStrategy) -> Prog
         &[b"shield", b"policy", mint.key()],
     )?;
 
-    let record = PolicyV2 {
-        kind: Kind::PolicyV2 as u8,
+    let record = PolicyV3 {
+        kind: Kind::PolicyV3 as u8,
         strategy,
         nonce,
         mint: *mint.key(),
-        identities_len: [0; 4],
+        revision: [0; REVISION_SIZE],
+        min_balance: min_balance.to_le_bytes(),
+        locked: 0,
+        identities_len: [0; IDENTITIES_LEN_SIZE],
     };
 
     let bump = &[nonce];
     let seed = seeds!(b"shield", b"policy", mint.key(), bump);
     let signer = Signer::from(&seed);
 
-    create_account(policy, payer, PolicyV2::LEN, &crate::ID, &[signer])?;
+    create_account(policy, payer, PolicyV3::LEN, &crate::ID, &[signer])?;
 
     let mut data = policy.try_borrow_mut_data()?;
 
-    unsafe { sol_memcpy(&mut data, bytes_of(&record), PolicyV2::LEN) };
+    unsafe { sol_memcpy(&mut data, bytes_of(&record), PolicyV3::LEN) };
+
+    PolicyEvent::new(EventKind::PolicyCreated, policy.key(), 0, 0).emit();
 
     Ok(())
 }
 
-fn add_identity(accounts: &[AccountInfo], identity: Pubkey) -> ProgramResult {
+fn add_identity(
+    accounts: &[AccountInfo],
+    identity: Pubkey,
+    expected_revision: Option<u64>,
+) -> ProgramResult {
     let [mint, token_account, policy, payer, owner, _system_program, ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    validate_policy_associated_accounts(owner, mint, token_account)?;
+    let header = PolicyHeader::read(&policy.try_borrow_data()?)?;
 
-    let (
-        identities_len_offset,
-        meta_len,
-        current_identities_count,
-        identities_count_from_buffer,
-        nonce,
-    ) = {
-        let data = policy.try_borrow_mut_data()?;
-        match Kind::try_from(data[0])? {
-            Kind::Policy => {
-                let policy = unsafe { Policy::from_bytes(&data[..Policy::LEN]) }?;
-                (
-                    Policy::IDENTITIES_BUFFER_OFFSET,
-                    Policy::LEN,
-                    policy.current_identities_len(),
-                    Policy::identities_len_from_buffer(data.len()),
-                    policy.nonce,
-                )
-            }
-            Kind::PolicyV2 => {
-                let policy_v2 = unsafe { PolicyV2::from_bytes(&data[..PolicyV2::LEN]) }?;
-                (
-                    PolicyV2::IDENTITIES_BUFFER_OFFSET,
-                    PolicyV2::LEN,
-                    policy_v2.current_identities_len(),
-                    PolicyV2::identities_len_from_buffer(data.len()),
-                    policy_v2.nonce,
-                )
-            }
-        }
-    };
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
+    assert_valid_identity(&identity)?;
 
     validate_pda(
         "policy",
         policy,
         &crate::ID,
-        &[b"shield", b"policy", mint.key(), &[nonce]],
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
     )?;
 
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
+
+    assert_identity_absent(&policy.try_borrow_data()?[header.meta_len..], &identity)?;
+
     realloc_account(policy, payer, policy.data_len() + BYTES_PER_PUBKEY)?;
 
-    let new_identity_offset = meta_len + identities_count_from_buffer * BYTES_PER_PUBKEY;
+    let new_identity_offset = header.meta_len + header.identities_capacity * BYTES_PER_PUBKEY;
 
     let mut data = policy.try_borrow_mut_data()?;
 
@@ -150,64 +187,65 @@ fn add_identity(accounts: &[AccountInfo], identity: Pubkey) -> ProgramResult {
         )
     };
 
-    let updated_identities_count: [u8; IDENTITIES_LEN_SIZE] =
-        (current_identities_count as u32 + 1).to_le_bytes();
+    let updated_identities_count = (header.identities_len as u32)
+        .checked_add(1)
+        .ok_or(ShieldError::NumericalOverflow)?;
 
-    unsafe {
-        sol_memcpy(
-            &mut data[identities_len_offset..identities_len_offset + IDENTITIES_LEN_SIZE],
-            &updated_identities_count,
-            IDENTITIES_LEN_SIZE,
-        )
-    };
+    header.write_identities_len(&mut data, updated_identities_count);
+    let revision = header.increment_revision(&mut data)?;
+
+    PolicyEvent::new(
+        EventKind::IdentityAdded,
+        policy.key(),
+        updated_identities_count,
+        revision,
+    )
+    .identity(&identity, header.identities_capacity)
+    .emit();
 
     Ok(())
 }
 
-fn remove_identity(accounts: &[AccountInfo], index: usize) -> ProgramResult {
+fn remove_identity(
+    accounts: &[AccountInfo],
+    index: usize,
+    expected_revision: Option<u64>,
+) -> ProgramResult {
     let [mint, token_account, policy, owner, ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    validate_policy_associated_accounts(owner, mint, token_account)?;
-
     let mut data = policy.try_borrow_mut_data()?;
 
-    let (identities_len_offset, meta_len, nonce, current_identities_count) =
-        match Kind::try_from(data[0])? {
-            Kind::Policy => {
-                let policy = unsafe { Policy::from_bytes(&data[..Policy::LEN]) }?;
-                (
-                    Policy::IDENTITIES_BUFFER_OFFSET,
-                    Policy::LEN,
-                    policy.nonce,
-                    policy.current_identities_len(),
-                )
-            }
-            Kind::PolicyV2 => {
-                let policy_v2 = unsafe { PolicyV2::from_bytes(&data[..PolicyV2::LEN]) }?;
-                (
-                    PolicyV2::IDENTITIES_BUFFER_OFFSET,
-                    PolicyV2::LEN,
-                    policy_v2.nonce,
-                    policy_v2.current_identities_len(),
-                )
-            }
-        };
+    let header = PolicyHeader::read(&data)?;
+
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
 
     validate_pda(
         "policy",
         policy,
         &crate::ID,
-        &[b"shield", b"policy", mint.key(), &[nonce]],
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
     )?;
 
-    let position = meta_len + index * BYTES_PER_PUBKEY;
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
+
+    let position = header.meta_len + index * BYTES_PER_PUBKEY;
 
     if position + BYTES_PER_PUBKEY > data.len() {
         return Err(ShieldError::InvalidIndexToReferenceIdentity.into());
     }
 
+    // Removing an empty slot would decrement the count without removing an identity.
+    let identity: Pubkey = data[position..position + BYTES_PER_PUBKEY]
+        .try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if identity == Pubkey::default() {
+        return Err(ShieldError::IdentitySlotEmpty.into());
+    }
+
     unsafe {
         sol_memcpy(
             &mut data[position..position + BYTES_PER_PUBKEY],
@@ -216,65 +254,64 @@ fn remove_identity(accounts: &[AccountInfo], index: usize) -> ProgramResult {
         );
     }
 
-    let updated_identities_count: [u8; IDENTITIES_LEN_SIZE] =
-        (current_identities_count as u32 - 1).to_le_bytes();
+    let updated_identities_count = (header.identities_len as u32)
+        .checked_sub(1)
+        .ok_or(ShieldError::NumericalOverflow)?;
 
-    unsafe {
-        sol_memcpy(
-            &mut data[identities_len_offset..identities_len_offset + IDENTITIES_LEN_SIZE],
-            &updated_identities_count,
-            IDENTITIES_LEN_SIZE,
-        )
-    };
+    header.write_identities_len(&mut data, updated_identities_count);
+    let revision = header.increment_revision(&mut data)?;
+
+    PolicyEvent::new(
+        EventKind::IdentityRemoved,
+        policy.key(),
+        updated_identities_count,
+        revision,
+    )
+    .identity(&identity, index)
+    .emit();
 
     Ok(())
 }
 
-fn replace_identity(accounts: &[AccountInfo], index: usize, identity: Pubkey) -> ProgramResult {
+fn replace_identity(
+    accounts: &[AccountInfo],
+    index: usize,
+    identity: Pubkey,
+    expected_revision: Option<u64>,
+) -> ProgramResult {
     let [mint, token_account, policy, owner, ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    validate_policy_associated_accounts(owner, mint, token_account)?;
-
     let mut data = policy.try_borrow_mut_data()?;
 
-    let (identities_len_offset, meta_len, nonce, current_identities_count) =
-        match Kind::try_from(data[0])? {
-            Kind::Policy => {
-                let policy = unsafe { Policy::from_bytes(&data[..Policy::LEN]) }?;
-                (
-                    Policy::IDENTITIES_BUFFER_OFFSET,
-                    Policy::LEN,
-                    policy.nonce,
-                    policy.current_identities_len(),
-                )
-            }
-            Kind::PolicyV2 => {
-                let policy_v2 = unsafe { PolicyV2::from_bytes(&data[..PolicyV2::LEN]) }?;
-                (
-                    PolicyV2::IDENTITIES_BUFFER_OFFSET,
-                    PolicyV2::LEN,
-                    policy_v2.nonce,
-                    policy_v2.current_identities_len(),
-                )
-            }
-        };
+    let header = PolicyHeader::read(&data)?;
+
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
+    assert_valid_identity(&identity)?;
 
     validate_pda(
         "policy",
         policy,
         &crate::ID,
-        &[b"shield", b"policy", mint.key(), &[nonce]],
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
     )?;
 
-    let position = meta_len + index * BYTES_PER_PUBKEY;
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
+
+    let position = header.meta_len + index * BYTES_PER_PUBKEY;
 
     if position + BYTES_PER_PUBKEY > data.len() {
         return Err(ShieldError::InvalidIndexToReferenceIdentity.into());
     }
 
-    let is_new_identity = data[position..position + BYTES_PER_PUBKEY] == Pubkey::default();
+    assert_identity_absent(&data[header.meta_len..], &identity)?;
+
+    let previous_identity: Pubkey = data[position..position + BYTES_PER_PUBKEY]
+        .try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let is_new_identity = previous_identity == Pubkey::default();
 
     unsafe {
         sol_memcpy(
@@ -284,38 +321,374 @@ fn replace_identity(accounts: &[AccountInfo], index: usize, identity: Pubkey) ->
         );
     }
 
+    let mut updated_identities_count = header.identities_len as u32;
+
     if is_new_identity {
-        let updated_identities_count: [u8; IDENTITIES_LEN_SIZE] =
-            (current_identities_count as u32 + 1).to_le_bytes();
+        updated_identities_count = updated_identities_count
+            .checked_add(1)
+            .ok_or(ShieldError::NumericalOverflow)?;
 
-        unsafe {
-            sol_memcpy(
-                &mut data[identities_len_offset..identities_len_offset + IDENTITIES_LEN_SIZE],
-                &updated_identities_count,
-                IDENTITIES_LEN_SIZE,
-            )
-        };
+        header.write_identities_len(&mut data, updated_identities_count);
     }
 
+    let revision = header.increment_revision(&mut data)?;
+
+    PolicyEvent::new(
+        EventKind::IdentityReplaced,
+        policy.key(),
+        updated_identities_count,
+        revision,
+    )
+    .identity(&identity, index)
+    .previous_identity(&previous_identity)
+    .emit();
+
     Ok(())
 }
 
-fn close_policy(accounts: &[AccountInfo]) -> ProgramResult {
+fn close_policy(accounts: &[AccountInfo], expected_revision: Option<u64>) -> ProgramResult {
     let [mint, token_account, policy, payer, owner, _system_program, ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    validate_policy_associated_accounts(owner, mint, token_account)?;
+    let header = PolicyHeader::read(&policy.try_borrow_data()?)?;
+
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
+
+    validate_pda(
+        "policy",
+        policy,
+        &crate::ID,
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
+    )?;
+
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
 
     close_account(policy, payer)?;
 
+    PolicyEvent::new(
+        EventKind::PolicyClosed,
+        policy.key(),
+        0,
+        header.current_revision(),
+    )
+    .emit();
+
+    Ok(())
+}
+
+fn remove_identity_by_key(
+    accounts: &[AccountInfo],
+    identity: Pubkey,
+    expected_revision: Option<u64>,
+) -> ProgramResult {
+    let [mint, token_account, policy, owner, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut data = policy.try_borrow_mut_data()?;
+
+    let header = PolicyHeader::read(&data)?;
+
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
+    assert_valid_identity(&identity)?;
+
+    validate_pda(
+        "policy",
+        policy,
+        &crate::ID,
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
+    )?;
+
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
+
+    let index = find_identity(&data[header.meta_len..], &identity)?;
+    let position = header.meta_len + index * BYTES_PER_PUBKEY;
+
+    unsafe {
+        sol_memcpy(
+            &mut data[position..position + BYTES_PER_PUBKEY],
+            Pubkey::default().as_slice(),
+            BYTES_PER_PUBKEY,
+        );
+    }
+
+    let updated_identities_count = (header.identities_len as u32)
+        .checked_sub(1)
+        .ok_or(ShieldError::NumericalOverflow)?;
+
+    header.write_identities_len(&mut data, updated_identities_count);
+    let revision = header.increment_revision(&mut data)?;
+
+    PolicyEvent::new(
+        EventKind::IdentityRemoved,
+        policy.key(),
+        updated_identities_count,
+        revision,
+    )
+    .identity(&identity, index)
+    .emit();
+
+    Ok(())
+}
+
+/// Replaces `old_identity` with `new_identity`.
+///
+/// An `old_identity` equal to the default public key fills the first empty slot instead.
+fn replace_identity_by_key(
+    accounts: &[AccountInfo],
+    old_identity: Pubkey,
+    new_identity: Pubkey,
+    expected_revision: Option<u64>,
+) -> ProgramResult {
+    let [mint, token_account, policy, owner, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut data = policy.try_borrow_mut_data()?;
+
+    let header = PolicyHeader::read(&data)?;
+
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
+    assert_valid_identity(&new_identity)?;
+
+    validate_pda(
+        "policy",
+        policy,
+        &crate::ID,
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
+    )?;
+
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
+
+    assert_identity_absent(&data[header.meta_len..], &new_identity)?;
+
+    let index = find_identity(&data[header.meta_len..], &old_identity)?;
+    let position = header.meta_len + index * BYTES_PER_PUBKEY;
+
+    unsafe {
+        sol_memcpy(
+            &mut data[position..position + BYTES_PER_PUBKEY],
+            new_identity.as_ref(),
+            BYTES_PER_PUBKEY,
+        );
+    }
+
+    let mut updated_identities_count = header.identities_len as u32;
+
+    if old_identity == Pubkey::default() {
+        updated_identities_count = updated_identities_count
+            .checked_add(1)
+            .ok_or(ShieldError::NumericalOverflow)?;
+
+        header.write_identities_len(&mut data, updated_identities_count);
+    }
+
+    let revision = header.increment_revision(&mut data)?;
+
+    PolicyEvent::new(
+        EventKind::IdentityReplaced,
+        policy.key(),
+        updated_identities_count,
+        revision,
+    )
+    .identity(&new_identity, index)
+    .previous_identity(&old_identity)
+    .emit();
+
     Ok(())
 }
 
+/// Rewrites a `Policy` or `PolicyV2` account as a `PolicyV3` account at revision 0.
+///
+/// The identities buffer is kept as is, empty slots included, and `identities_len` is
+/// recounted from it.
+fn migrate_policy(accounts: &[AccountInfo]) -> ProgramResult {
+    let [mint, token_account, policy, payer, owner, _system_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (header, strategy) = {
+        let data = policy.try_borrow_data()?;
+        (PolicyHeader::read(&data)?, data[1])
+    };
+
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
+
+    validate_pda(
+        "policy",
+        policy,
+        &crate::ID,
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
+    )?;
+
+    if header.revision.is_some() {
+        return Err(ShieldError::PolicyAlreadyMigrated.into());
+    }
+
+    // The identities buffer fills the rest of the account. A buffer that is not a whole number
+    // of identities is rejected rather than truncated by the move.
+    let identities_size = policy
+        .data_len()
+        .checked_sub(header.meta_len)
+        .ok_or(ShieldError::InvalidAccountData)?;
+    if identities_size % BYTES_PER_PUBKEY != 0 {
+        return Err(ShieldError::InvalidAccountData.into());
+    }
+
+    let identities_len = {
+        let data = policy.try_borrow_data()?;
+        data[header.meta_len..header.meta_len + identities_size]
+            .chunks_exact(BYTES_PER_PUBKEY)
+            .filter(|identity| *identity != Pubkey::default())
+            .count() as u32
+    };
+
+    realloc_account(policy, payer, PolicyV3::LEN + identities_size)?;
+
+    let mut data = policy.try_borrow_mut_data()?;
+
+    // The identities move to a higher offset overlapping their current range.
+    data.copy_within(
+        header.meta_len..header.meta_len + identities_size,
+        PolicyV3::LEN,
+    );
+
+    let record = PolicyV3 {
+        kind: Kind::PolicyV3 as u8,
+        strategy,
+        nonce: header.nonce,
+        mint: *mint.key(),
+        revision: [0; REVISION_SIZE],
+        min_balance: [0; MIN_BALANCE_SIZE],
+        locked: 0,
+        identities_len: identities_len.to_le_bytes(),
+    };
+
+    unsafe { sol_memcpy(&mut data, bytes_of(&record), PolicyV3::LEN) };
+
+    PolicyEvent::new(EventKind::PolicyMigrated, policy.key(), identities_len, 0).emit();
+
+    Ok(())
+}
+
+/// Sets the minimum balance of a `PolicyV3` account, legacy kinds must be migrated first.
+fn set_min_balance(
+    accounts: &[AccountInfo],
+    min_balance: u64,
+    expected_revision: Option<u64>,
+) -> ProgramResult {
+    let [mint, token_account, policy, owner, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut data = policy.try_borrow_mut_data()?;
+
+    let header = PolicyHeader::read(&data)?;
+
+    // The owner must meet the current threshold to change it, and the new one so the policy
+    // is not left without any holder able to update it.
+    validate_policy_associated_accounts(
+        owner,
+        mint,
+        token_account,
+        header.min_balance.max(min_balance),
+    )?;
+
+    validate_pda(
+        "policy",
+        policy,
+        &crate::ID,
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
+    )?;
+
+    if header.revision.is_none() {
+        return Err(ShieldError::PolicyNotMigrated.into());
+    }
+
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
+
+    unsafe {
+        sol_memcpy(
+            &mut data
+                [PolicyV3::MIN_BALANCE_OFFSET..PolicyV3::MIN_BALANCE_OFFSET + MIN_BALANCE_SIZE],
+            &min_balance.to_le_bytes(),
+            MIN_BALANCE_SIZE,
+        )
+    };
+
+    let revision = header.increment_revision(&mut data)?;
+
+    PolicyEvent::new(
+        EventKind::MinBalanceSet,
+        policy.key(),
+        header.identities_len as u32,
+        revision,
+    )
+    .emit();
+
+    Ok(())
+}
+
+/// Locks a `PolicyV3` account, legacy kinds must be migrated first. Locking is irreversible:
+/// the identities can no longer be changed and the policy can no longer be closed.
+fn lock_policy(accounts: &[AccountInfo], expected_revision: Option<u64>) -> ProgramResult {
+    let [mint, token_account, policy, owner, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut data = policy.try_borrow_mut_data()?;
+
+    let header = PolicyHeader::read(&data)?;
+
+    validate_policy_associated_accounts(owner, mint, token_account, header.min_balance)?;
+
+    validate_pda(
+        "policy",
+        policy,
+        &crate::ID,
+        &[b"shield", b"policy", mint.key(), &[header.nonce]],
+    )?;
+
+    if header.revision.is_none() {
+        return Err(ShieldError::PolicyNotMigrated.into());
+    }
+
+    header.assert_revision(expected_revision)?;
+    header.assert_unlocked()?;
+
+    unsafe {
+        sol_memcpy(
+            &mut data[PolicyV3::LOCKED_OFFSET..PolicyV3::LOCKED_OFFSET + LOCKED_SIZE],
+            &[1],
+            LOCKED_SIZE,
+        )
+    };
+
+    let revision = header.increment_revision(&mut data)?;
+
+    PolicyEvent::new(
+        EventKind::PolicyLocked,
+        policy.key(),
+        header.identities_len as u32,
+        revision,
+    )
+    .emit();
+
+    Ok(())
+}
+
+/// Assert that `owner` holds the policy token in its associated token account, at least
+/// `min_balance` of it when set.
 fn validate_policy_associated_accounts(
     owner: &AccountInfo,
     mint: &AccountInfo,
     token_account: &AccountInfo,
+    min_balance: u64,
 ) -> ProgramResult {
     assert_signer("owner", owner)?;
     assert_program_owner("mint", mint, &spl_token_2022::id().to_bytes())?;
@@ -336,6 +709,157 @@ fn validate_policy_associated_accounts(
     assert_mint_association("token_account", mint.key(), &account)?;
     assert_token_owner("token_account", owner.key(), &account)?;
     assert_positive_amount("token_account", &account)?;
+    assert_min_balance("token_account", &account, min_balance)?;
 
     Ok(())
 }
+
+/// The header fields shared by every policy kind.
+struct PolicyHeader {
+    /// Offset of the identities length in the account data.
+    identities_len_offset: usize,
+    /// Length of the header, the identities buffer starts right after it.
+    meta_len: usize,
+    nonce: u8,
+    /// Number of non-empty slots recorded in the header.
+    identities_len: usize,
+    /// Number of slots of the identities buffer, including empty slots.
+    identities_capacity: usize,
+    /// Offset and value of the revision, `None` for kinds without a revision.
+    revision: Option<(usize, u64)>,
+    /// Token balance required to mutate the policy, `0` for kinds without a minimum balance.
+    min_balance: u64,
+    /// Whether the policy was locked, only `PolicyV3` accounts can be locked.
+    locked: bool,
+}
+
+impl PolicyHeader {
+    fn read(data: &[u8]) -> Result<Self, ProgramError> {
+        match Kind::try_from(data[0])? {
+            Kind::Policy => {
+                let policy = unsafe { Policy::from_bytes(&data[..Policy::LEN]) }?;
+                Ok(Self {
+                    identities_len_offset: Policy::IDENTITIES_BUFFER_OFFSET,
+                    meta_len: Policy::LEN,
+                    nonce: policy.nonce,
+                    identities_len: policy.current_identities_len(),
+                    identities_capacity: Policy::identities_len_from_buffer(data.len()),
+                    revision: None,
+                    min_balance: 0,
+                    locked: false,
+                })
+            }
+            Kind::PolicyV2 => {
+                let policy_v2 = unsafe { PolicyV2::from_bytes(&data[..PolicyV2::LEN]) }?;
+                Ok(Self {
+                    identities_len_offset: PolicyV2::IDENTITIES_BUFFER_OFFSET,
+                    meta_len: PolicyV2::LEN,
+                    nonce: policy_v2.nonce,
+                    identities_len: policy_v2.current_identities_len(),
+                    identities_capacity: PolicyV2::identities_len_from_buffer(data.len()),
+                    revision: None,
+                    min_balance: 0,
+                    locked: false,
+                })
+            }
+            Kind::PolicyV3 => {
+                let policy_v3 = unsafe { PolicyV3::from_bytes(&data[..PolicyV3::LEN]) }?;
+                Ok(Self {
+                    identities_len_offset: PolicyV3::IDENTITIES_BUFFER_OFFSET,
+                    meta_len: PolicyV3::LEN,
+                    nonce: policy_v3.nonce,
+                    identities_len: policy_v3.current_identities_len(),
+                    identities_capacity: PolicyV3::identities_len_from_buffer(data.len()),
+                    revision: Some((PolicyV3::REVISION_OFFSET, policy_v3.current_revision())),
+                    min_balance: policy_v3.current_min_balance(),
+                    locked: policy_v3.is_locked(),
+                })
+            }
+        }
+    }
+
+    /// Assert that the policy is at the `expected` revision, if any.
+    ///
+    /// Kinds without a revision only accept mutations without an expected revision.
+    fn assert_revision(&self, expected: Option<u64>) -> ProgramResult {
+        let Some(expected) = expected else {
+            return Ok(());
+        };
+
+        match self.revision {
+            Some((_, revision)) if revision == expected => Ok(()),
+            Some((_, revision)) => {
+                msg!(
+                    "Policy is at revision {}, expected revision {}",
+                    revision,
+                    expected
+                );
+                Err(ShieldError::RevisionMismatch.into())
+            }
+            None => {
+                msg!("Policy has no revision, expected revision {}", expected);
+                Err(ShieldError::RevisionMismatch.into())
+            }
+        }
+    }
+
+    /// Assert that the policy was not locked.
+    fn assert_unlocked(&self) -> ProgramResult {
+        if self.locked {
+            msg!("Policy is locked");
+            return Err(ShieldError::Immutable.into());
+        }
+
+        Ok(())
+    }
+
+    fn write_identities_len(&self, data: &mut [u8], identities_len: u32) {
+        unsafe {
+            sol_memcpy(
+                &mut data
+                    [self.identities_len_offset..self.identities_len_offset + IDENTITIES_LEN_SIZE],
+                &identities_len.to_le_bytes(),
+                IDENTITIES_LEN_SIZE,
+            )
+        };
+    }
+
+    /// The revision of the policy, `0` for kinds without a revision.
+    fn current_revision(&self) -> u64 {
+        self.revision.map_or(0, |(_, revision)| revision)
+    }
+
+    /// Increment the revision of kinds that record one.
+    ///
+    /// Returns the new revision, `0` for kinds without a revision.
+    fn increment_revision(&self, data: &mut [u8]) -> Result<u64, ProgramError> {
+        let Some((offset, revision)) = self.revision else {
+            return Ok(0);
+        };
+
+        let revision = revision
+            .checked_add(1)
+            .ok_or(ShieldError::NumericalOverflow)?;
+
+        unsafe {
+            sol_memcpy(
+                &mut data[offset..offset + REVISION_SIZE],
+                &revision.to_le_bytes(),
+                REVISION_SIZE,
+            )
+        };
+
+        Ok(revision)
+    }
+}
+
+/// Returns the index of the first slot of the identities buffer holding `identity`.
+fn find_identity(identities: &[u8], identity: &Pubkey) -> Result<usize, ProgramError> {
+    identities
+        .chunks_exact(BYTES_PER_PUBKEY)
+        .position(|existing| existing == identity)
+        .ok_or_else(|| {
+            msg!("Identity [{:?}] not found in the policy", identity);
+            ShieldError::IdentityNotFound.into()
+        })
+}
@@ -35,6 +35,7 @@ pub trait Size {
 pub enum Kind {
     Policy,
     PolicyV2,
+    PolicyV3,
 }
 
 impl TryFrom<u8> for Kind {
@@ -43,6 +44,7 @@ impl TryFrom<u8> for Kind {
         match value {
             0 => Ok(Self::Policy),
             1 => Ok(Self::PolicyV2),
+            2 => Ok(Self::PolicyV3),
             _ => Err(ShieldError::InvalidPolicyKind.into()),
         }
     }
@@ -58,6 +60,12 @@ pub enum PermissionStrategy {
 
 pub const IDENTITIES_LEN_SIZE: usize = 4;
 
+pub const REVISION_SIZE: usize = 8;
+
+pub const MIN_BALANCE_SIZE: usize = 8;
+
+pub const LOCKED_SIZE: usize = 1;
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable, ShankAccount)]
 pub struct Policy {
@@ -120,3 +128,59 @@ impl Size for PolicyV2 {
 }
 
 impl ZeroCopyLoad for PolicyV2 {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, ShankAccount)]
+pub struct PolicyV3 {
+    pub kind: u8,
+    pub strategy: u8,
+    pub nonce: u8,
+    pub mint: Pubkey,
+    /// Incremented by every mutation of the policy.
+    pub revision: [u8; 8],
+    /// Token balance required to mutate the policy, any positive balance when `0`.
+    pub min_balance: [u8; 8],
+    /// Set by `LockPolicy`, a locked policy can no longer be mutated or closed.
+    pub locked: u8,
+    pub identities_len: [u8; 4],
+}
+
+impl PolicyV3 {
+    pub const REVISION_OFFSET: usize = 3 + BYTES_PER_PUBKEY;
+
+    pub const MIN_BALANCE_OFFSET: usize = Self::REVISION_OFFSET + REVISION_SIZE;
+
+    pub const LOCKED_OFFSET: usize = Self::MIN_BALANCE_OFFSET + MIN_BALANCE_SIZE;
+
+    pub const IDENTITIES_BUFFER_OFFSET: usize = Self::LOCKED_OFFSET + LOCKED_SIZE;
+
+    pub fn current_identities_len(&self) -> usize {
+        u32::from_le_bytes(self.identities_len) as usize
+    }
+
+    pub fn current_revision(&self) -> u64 {
+        u64::from_le_bytes(self.revision)
+    }
+
+    pub fn current_min_balance(&self) -> u64 {
+        u64::from_le_bytes(self.min_balance)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked != 0
+    }
+
+    pub fn identities_len_from_buffer(acc_data_len: usize) -> usize {
+        if acc_data_len > PolicyV3::LEN && (acc_data_len - PolicyV3::LEN) % BYTES_PER_PUBKEY == 0 {
+            (acc_data_len - PolicyV3::LEN) / BYTES_PER_PUBKEY
+        } else {
+            0
+        }
+    }
+}
+
+impl Size for PolicyV3 {
+    const LEN: usize = core::mem::size_of::<Self>();
+}
+
+impl ZeroCopyLoad for PolicyV3 {}
@@ -55,7 +55,27 @@ codama.update(
   })
 );
 
+codama.update(
+  c.updateAccountsVisitor({
+    policy_v3: {
+      size: 56,
+      seeds: [
+        c.constantPdaSeedNodeFromString('utf8', 'shield'),
+        c.constantPdaSeedNodeFromString('utf8', 'policy'),
+        c.variablePdaSeedNode(
+          'mint',
+          c.publicKeyTypeNode(),
+          'The mint of the token extension account'
+        ),
+      ],
+    },
+  })
+);
+
 // Update instructions.
+const expectedRevision = {
+  expectedRevision: { defaultValue: c.noneValueNode() },
+};
 codama.update(
   c.updateInstructionsVisitor({
     createPolicy: {
@@ -64,6 +84,9 @@ codama.update(
         policy: { defaultValue: c.pdaValueNode('policy') },
         owner: { defaultValue: c.accountValueNode('payer') },
       },
+      arguments: {
+        minBalance: { defaultValue: c.noneValueNode() },
+      },
     },
     addIdentity: {
       byteDeltas: [c.instructionByteDeltaNode(c.accountLinkNode('policy'))],
@@ -71,6 +94,7 @@ codama.update(
         policy: { defaultValue: c.pdaValueNode('policy') },
         owner: { defaultValue: c.accountValueNode('payer') },
       },
+      arguments: expectedRevision,
     },
     closePolicy: {
       byteDeltas: [c.instructionByteDeltaNode(c.accountLinkNode('policy'))],
@@ -78,7 +102,20 @@ codama.update(
         policy: { defaultValue: c.pdaValueNode('policy') },
         owner: { defaultValue: c.accountValueNode('payer') },
       },
+      arguments: expectedRevision,
     },
+    migratePolicy: {
+      accounts: {
+        policy: { defaultValue: c.pdaValueNode('policy') },
+        owner: { defaultValue: c.accountValueNode('payer') },
+      },
+    },
+    removeIdentity: { arguments: expectedRevision },
+    replaceIdentity: { arguments: expectedRevision },
+    removeIdentityByKey: { arguments: expectedRevision },
+    replaceIdentityByKey: { arguments: expectedRevision },
+    setMinBalance: { arguments: expectedRevision },
+    lockPolicy: { arguments: expectedRevision },
   })
 );
 
@@ -106,3 +143,57 @@ codama.accept(
     crateFolder: rustClient,
   })
 );
+
+// Render the Vixen instruction parser. Only the instruction parser is copied
+// over, policy accounts are decoded by the hand-written `accounts_parser`
+// module since every account version shares the same discriminator field.
+const parser = path.join(__dirname, '..', 'parser');
+const parserProject = path.join(os.tmpdir(), 'yellowstone-shield-parser');
+await fs.remove(parserProject);
+codama.accept(
+  renderParserVisitor({
+    projectFolder: parserProject,
+    projectName: 'yellowstone-shield-parser',
+    sdkName: 'yellowstone_shield_client',
+    generateProto: false,
+    formatCode: true,
+  })
+);
+const renderedParser = await fs.readFile(
+  path.join(parserProject, 'src', 'generated_parser', 'instructions_parser.rs'),
+  'utf8'
+);
+
+// The renderer slices the discriminator out of the instruction data, which
+// panics on empty data. Split it off instead so the parser returns an error,
+// and decode the data of instructions predating their trailing optional
+// argument the way the program does.
+const patches = [
+  [
+    `        let ix_discriminator: [u8; 1] = ix.data[0..1].try_into()?;
+        let mut ix_data = &ix.data[1..];
+`,
+    `        let (ix_discriminator, mut ix_data) = ix.data.split_first().ok_or_else(|| {
+            yellowstone_vixen_core::ParseError::from("Instruction data is empty".to_owned())
+        })?;
+        // Like `ShieldInstruction::unpack`, accept `CreatePolicy` to `ClosePolicy` data without
+        // their trailing `Option` argument. Trailing bytes are ignored, so a `None` is appended.
+        let legacy_data;
+        if *ix_discriminator <= 4 {
+            legacy_data = [ix_data, &[0][..]].concat();
+            ix_data = &legacy_data;
+        }
+`,
+  ],
+  ['        match ix_discriminator {', '        match [*ix_discriminator] {'],
+];
+const patchedParser = patches.reduce((source, [from, to]) => {
+  if (!source.includes(from)) {
+    throw new Error(`Rendered instruction parser no longer contains: ${from}`);
+  }
+  return source.replace(from, to);
+}, renderedParser);
+await fs.writeFile(
+  path.join(parser, 'src', 'generated', 'instructions_parser.rs'),
+  patchedParser
+);
@@ -5,7 +5,8 @@ use solana_cli_config::{Config, CONFIG_FILE};
 use solana_pubkey::pubkey;
 use solana_pubkey::Pubkey;
 use yellowstone_shield_cli::{
-    run, Command, CommandComplete, IdentitiesAction, PolicyAction, SolanaAccount,
+    run, Command, CommandComplete, IdentitiesAction, IdentitiesInput, PolicyAction, RunOptions,
+    SolanaAccount,
 };
 use yellowstone_shield_store::{PolicyStore, PolicyStoreConfig, PolicyStoreTrait};
 
@@ -48,7 +49,7 @@ async fn main() {
 
             let other = Pubkey::new_unique();
 
-            let CommandComplete(SolanaAccount(allow, _), _) = run(
+            let CommandComplete::Policy(SolanaAccount(allow, _), _) = run(
                 Arc::clone(&cli),
                 Command::Policy {
                     action: PolicyAction::Create {
@@ -58,21 +59,30 @@ async fn main() {
                         uri: "https://test.com/good.json".to_string(),
                     },
                 },
+                RunOptions::default(),
             )
             .await
-            .unwrap();
+            .unwrap() else {
+                panic!("expected a policy");
+            };
             let allow = run(
                 Arc::clone(&cli),
                 Command::Identities {
                     action: IdentitiesAction::Add {
                         mint: allow,
-                        identities_path: PathBuf::from("./identities-good-demo.txt"),
+                        input: IdentitiesInput {
+                            identities_path: Some(PathBuf::from("./identities-good-demo.txt")),
+                            ..Default::default()
+                        },
+                        dry_run: false,
+                        yes: true,
                     },
                 },
+                RunOptions::default(),
             )
             .await
             .unwrap();
-            let CommandComplete(SolanaAccount(deny, _), _) = run(
+            let CommandComplete::Policy(SolanaAccount(deny, _), _) = run(
                 Arc::clone(&cli),
                 Command::Policy {
                     action: PolicyAction::Create {
@@ -82,30 +92,43 @@ async fn main() {
                         uri: "https://test.com/bad.json".to_string(),
                     },
                 },
+                RunOptions::default(),
             )
             .await
-            .unwrap();
+            .unwrap() else {
+                panic!("expected a policy");
+            };
             let deny = run(
                 Arc::clone(&cli),
                 Command::Identities {
                     action: IdentitiesAction::Add {
                         mint: deny,
-                        identities_path: PathBuf::from("./identities-bad-demo.txt"),
+                        input: IdentitiesInput {
+                            identities_path: Some(PathBuf::from("./identities-bad-demo.txt")),
+                            ..Default::default()
+                        },
+                        dry_run: false,
+                        yes: true,
                     },
                 },
+                RunOptions::default(),
             )
             .await
             .unwrap();
 
             let snapshot = policy_store.snapshot();
 
-            let CommandComplete(_, SolanaAccount(address, _)) = deny;
+            let CommandComplete::Policy(_, SolanaAccount(address, _)) = deny else {
+                panic!("expected a policy");
+            };
 
             assert_eq!(snapshot.is_allowed(&[address], &good), Ok(true));
             assert_eq!(snapshot.is_allowed(&[address], &other), Ok(true));
             assert_eq!(snapshot.is_allowed(&[address], &bad), Ok(false));
 
-            let CommandComplete(_, SolanaAccount(address, _)) = allow;
+            let CommandComplete::Policy(_, SolanaAccount(address, _)) = allow else {
+                panic!("expected a policy");
+            };
 
             assert_eq!(snapshot.is_allowed(&[address], &good), Ok(true));
             assert_eq!(snapshot.is_allowed(&[address], &other), Ok(false));
@@ -0,0 +1,519 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+use solana_pubkey::Pubkey;
+use tokio::sync::mpsc;
+use yellowstone_shield_client::types::PermissionStrategy;
+use yellowstone_shield_parser::instructions_parser::{InstructionParser, ShieldProgramIx};
+use yellowstone_vixen_core::{
+    instruction::InstructionUpdate, ParseError, ParseResult, Parser, Prefilter,
+};
+
+use crate::PolicyCache;
+
+/// Number of records buffered between the audit handler and the sink writer.
+const AUDIT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// A change made to a policy, derived from a single Shield instruction.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event")]
+pub enum PolicyChangeEvent {
+    PolicyCreated {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        #[serde_as(as = "DisplayFromStr")]
+        mint: Pubkey,
+        strategy: PermissionStrategy,
+        min_balance: Option<u64>,
+    },
+    IdentityAdded {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        #[serde_as(as = "DisplayFromStr")]
+        identity: Pubkey,
+    },
+    IdentityRemoved {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        index: u64,
+        /// The identity held by the slot, resolved from the policy cache. `None` when the
+        /// cache had no version of the policy older than the instruction.
+        #[serde_as(as = "Option<DisplayFromStr>")]
+        identity: Option<Pubkey>,
+    },
+    IdentityReplaced {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        index: u64,
+        #[serde_as(as = "DisplayFromStr")]
+        identity: Pubkey,
+    },
+    IdentityRemovedByKey {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        #[serde_as(as = "DisplayFromStr")]
+        identity: Pubkey,
+    },
+    IdentityReplacedByKey {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        /// The replaced identity, the default public key when an empty slot was filled.
+        #[serde_as(as = "DisplayFromStr")]
+        old_identity: Pubkey,
+        #[serde_as(as = "DisplayFromStr")]
+        identity: Pubkey,
+    },
+    PolicyClosed {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+    },
+    PolicyMigrated {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        #[serde_as(as = "DisplayFromStr")]
+        mint: Pubkey,
+    },
+    MinBalanceSet {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+        min_balance: u64,
+    },
+    PolicyLocked {
+        #[serde_as(as = "DisplayFromStr")]
+        policy: Pubkey,
+    },
+}
+
+impl PolicyChangeEvent {
+    /// The policy account the event applies to.
+    pub fn policy(&self) -> &Pubkey {
+        match self {
+            PolicyChangeEvent::PolicyCreated { policy, .. }
+            | PolicyChangeEvent::IdentityAdded { policy, .. }
+            | PolicyChangeEvent::IdentityRemoved { policy, .. }
+            | PolicyChangeEvent::IdentityReplaced { policy, .. }
+            | PolicyChangeEvent::IdentityRemovedByKey { policy, .. }
+            | PolicyChangeEvent::IdentityReplacedByKey { policy, .. }
+            | PolicyChangeEvent::PolicyClosed { policy }
+            | PolicyChangeEvent::PolicyMigrated { policy, .. }
+            | PolicyChangeEvent::MinBalanceSet { policy, .. }
+            | PolicyChangeEvent::PolicyLocked { policy } => policy,
+        }
+    }
+}
+
+impl From<&ShieldProgramIx> for PolicyChangeEvent {
+    fn from(ix: &ShieldProgramIx) -> Self {
+        match ix {
+            ShieldProgramIx::CreatePolicy(accounts, data) => PolicyChangeEvent::PolicyCreated {
+                policy: accounts.policy,
+                mint: accounts.mint,
+                strategy: data.strategy,
+                min_balance: data.min_balance,
+            },
+            ShieldProgramIx::AddIdentity(accounts, data) => PolicyChangeEvent::IdentityAdded {
+                policy: accounts.policy,
+                identity: data.identity,
+            },
+            ShieldProgramIx::RemoveIdentity(accounts, data) => PolicyChangeEvent::IdentityRemoved {
+                policy: accounts.policy,
+                index: data.index,
+                identity: None,
+            },
+            ShieldProgramIx::ReplaceIdentity(accounts, data) => {
+                PolicyChangeEvent::IdentityReplaced {
+                    policy: accounts.policy,
+                    index: data.index,
+                    identity: data.identity,
+                }
+            }
+            ShieldProgramIx::ClosePolicy(accounts, _) => PolicyChangeEvent::PolicyClosed {
+                policy: accounts.policy,
+            },
+            ShieldProgramIx::RemoveIdentityByKey(accounts, data) => {
+                PolicyChangeEvent::IdentityRemovedByKey {
+                    policy: accounts.policy,
+                    identity: data.identity,
+                }
+            }
+            ShieldProgramIx::ReplaceIdentityByKey(accounts, data) => {
+                PolicyChangeEvent::IdentityReplacedByKey {
+                    policy: accounts.policy,
+                    old_identity: data.old_identity,
+                    identity: data.new_identity,
+                }
+            }
+            ShieldProgramIx::MigratePolicy(accounts) => PolicyChangeEvent::PolicyMigrated {
+                policy: accounts.policy,
+                mint: accounts.mint,
+            },
+            ShieldProgramIx::SetMinBalance(accounts, data) => PolicyChangeEvent::MinBalanceSet {
+                policy: accounts.policy,
+                min_balance: data.min_balance,
+            },
+            ShieldProgramIx::LockPolicy(accounts, _) => PolicyChangeEvent::PolicyLocked {
+                policy: accounts.policy,
+            },
+        }
+    }
+}
+
+/// Returns the token owner that signed the instruction.
+fn instruction_owner(ix: &ShieldProgramIx) -> Pubkey {
+    match ix {
+        ShieldProgramIx::CreatePolicy(accounts, _) => accounts.owner,
+        ShieldProgramIx::AddIdentity(accounts, _) => accounts.owner,
+        ShieldProgramIx::RemoveIdentity(accounts, _) => accounts.owner,
+        ShieldProgramIx::ReplaceIdentity(accounts, _) => accounts.owner,
+        ShieldProgramIx::ClosePolicy(accounts, _) => accounts.owner,
+        ShieldProgramIx::RemoveIdentityByKey(accounts, _) => accounts.owner,
+        ShieldProgramIx::ReplaceIdentityByKey(accounts, _) => accounts.owner,
+        ShieldProgramIx::MigratePolicy(accounts) => accounts.owner,
+        ShieldProgramIx::SetMinBalance(accounts, _) => accounts.owner,
+        ShieldProgramIx::LockPolicy(accounts, _) => accounts.owner,
+    }
+}
+
+/// A policy change event joined with the transaction it was observed in.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyChangeRecord {
+    pub slot: u64,
+    pub signature: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub owner: Pubkey,
+    #[serde(flatten)]
+    pub event: PolicyChangeEvent,
+}
+
+impl PolicyChangeRecord {
+    /// Builds a record from a parsed instruction and the update it was parsed from.
+    pub fn new(ix_update: &InstructionUpdate, ix: &ShieldProgramIx) -> Self {
+        Self {
+            slot: ix_update.shared.slot,
+            signature: bs58::encode(&ix_update.shared.signature).into_string(),
+            owner: instruction_owner(ix),
+            event: ix.into(),
+        }
+    }
+
+    /// Fills in the identity of an `IdentityRemoved` event from the version of the policy
+    /// cached before the instruction's slot.
+    pub fn resolve_removed_identity(&mut self, cache: &PolicyCache) {
+        let PolicyChangeEvent::IdentityRemoved {
+            policy,
+            index,
+            identity: identity @ None,
+        } = &mut self.event
+        else {
+            return;
+        };
+
+        *identity = cache
+            .get_before(policy, self.slot)
+            .and_then(|policy| policy.identities.get(*index as usize).copied())
+            .filter(|identity| *identity != Pubkey::default());
+    }
+}
+
+/// Parses Shield instructions into `PolicyChangeRecord`s.
+///
+/// Wraps `InstructionParser` so the slot and signature of the transaction are kept
+/// alongside the decoded instruction.
+#[derive(Debug, Copy, Clone)]
+pub struct AuditParser;
+
+impl Parser for AuditParser {
+    type Input = InstructionUpdate;
+    type Output = PolicyChangeRecord;
+
+    fn id(&self) -> std::borrow::Cow<str> {
+        "shield::AuditParser".into()
+    }
+
+    fn prefilter(&self) -> Prefilter {
+        InstructionParser.prefilter()
+    }
+
+    async fn parse(&self, ix_update: &InstructionUpdate) -> ParseResult<Self::Output> {
+        // Instructions of failed transactions did not change any policy.
+        if ix_update.shared.err.is_some() {
+            return Err(ParseError::Filtered);
+        }
+
+        let ix = InstructionParser.parse(ix_update).await?;
+
+        Ok(PolicyChangeRecord::new(ix_update, &ix))
+    }
+}
+
+/// Destination for policy change records.
+pub trait AuditSink: Send + Sync {
+    fn write(&self, record: &PolicyChangeRecord) -> Result<()>;
+}
+
+impl<T: AuditSink + ?Sized> AuditSink for Box<T> {
+    fn write(&self, record: &PolicyChangeRecord) -> Result<()> {
+        (**self).write(record)
+    }
+}
+
+/// Writes each record as a single JSON line.
+pub struct JsonlSink<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonlSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Consumes the sink and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+impl JsonlSink<BufWriter<File>> {
+    /// Opens the file at `path` for appending, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write + Send> AuditSink for JsonlSink<W> {
+    fn write(&self, record: &PolicyChangeRecord) -> Result<()> {
+        let mut writer = self.writer.lock();
+        serde_json::to_writer(&mut *writer, record)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Vixen handler forwarding policy change records to an `AuditSink`.
+///
+/// Records are written by a blocking task fed through a channel, so sinks doing file I/O
+/// never block the runtime workers.
+pub struct AuditHandler {
+    records: mpsc::Sender<PolicyChangeRecord>,
+    cache: Arc<PolicyCache>,
+}
+
+impl AuditHandler {
+    /// Spawns the task writing records to `sink`, resolving removed identities from `cache`.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn(sink: impl AuditSink + 'static, cache: Arc<PolicyCache>) -> Self {
+        let (records, mut receiver) = mpsc::channel::<PolicyChangeRecord>(AUDIT_CHANNEL_CAPACITY);
+
+        tokio::task::spawn_blocking(move || {
+            while let Some(record) = receiver.blocking_recv() {
+                if let Err(e) = sink.write(&record) {
+                    log::error!("Failed to write audit record: {:?}", e);
+                }
+            }
+        });
+
+        Self { records, cache }
+    }
+}
+
+impl std::fmt::Debug for AuditHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditHandler").finish_non_exhaustive()
+    }
+}
+
+impl yellowstone_vixen::Handler<PolicyChangeRecord> for AuditHandler {
+    async fn handle(&self, value: &PolicyChangeRecord) -> yellowstone_vixen::HandlerResult<()> {
+        let mut record = value.clone();
+        record.resolve_removed_identity(&self.cache);
+
+        if self.records.send(record).await.is_err() {
+            log::error!("Audit writer stopped, dropping record");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_shield_client::instructions::{
+        AddIdentity, AddIdentityInstructionArgs, RemoveIdentity, RemoveIdentityByKey,
+        RemoveIdentityByKeyInstructionArgs, RemoveIdentityInstructionArgs,
+    };
+    use yellowstone_shield_parser::accounts_parser::Policy;
+
+    #[test]
+    fn test_event_from_instruction() {
+        let policy = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let identity = Pubkey::new_unique();
+
+        let add = ShieldProgramIx::AddIdentity(
+            AddIdentity {
+                mint: Pubkey::new_unique(),
+                token_account: Pubkey::new_unique(),
+                policy,
+                payer: owner,
+                owner,
+                system_program: Pubkey::default(),
+            },
+            AddIdentityInstructionArgs {
+                identity,
+                expected_revision: None,
+            },
+        );
+
+        assert_eq!(
+            PolicyChangeEvent::from(&add),
+            PolicyChangeEvent::IdentityAdded { policy, identity }
+        );
+        assert_eq!(instruction_owner(&add), owner);
+
+        let remove = ShieldProgramIx::RemoveIdentity(
+            RemoveIdentity {
+                mint: Pubkey::new_unique(),
+                token_account: Pubkey::new_unique(),
+                policy,
+                owner,
+            },
+            RemoveIdentityInstructionArgs {
+                index: 2,
+                expected_revision: Some(1),
+            },
+        );
+
+        assert_eq!(
+            PolicyChangeEvent::from(&remove),
+            PolicyChangeEvent::IdentityRemoved {
+                policy,
+                index: 2,
+                identity: None,
+            }
+        );
+
+        let remove_by_key = ShieldProgramIx::RemoveIdentityByKey(
+            RemoveIdentityByKey {
+                mint: Pubkey::new_unique(),
+                token_account: Pubkey::new_unique(),
+                policy,
+                owner,
+            },
+            RemoveIdentityByKeyInstructionArgs {
+                identity,
+                expected_revision: None,
+            },
+        );
+
+        assert_eq!(
+            PolicyChangeEvent::from(&remove_by_key),
+            PolicyChangeEvent::IdentityRemovedByKey { policy, identity }
+        );
+        assert_eq!(instruction_owner(&remove_by_key), owner);
+    }
+
+    #[test]
+    fn test_resolve_removed_identity() {
+        let policy = Pubkey::new_unique();
+        let identities = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let cache = PolicyCache::new();
+        cache.insert(
+            policy,
+            10,
+            Policy::new(PermissionStrategy::Deny, identities.clone()),
+        );
+
+        let removed = |slot, index| PolicyChangeRecord {
+            slot,
+            signature: "sig".to_string(),
+            owner: Pubkey::new_unique(),
+            event: PolicyChangeEvent::IdentityRemoved {
+                policy,
+                index,
+                identity: None,
+            },
+        };
+
+        let mut record = removed(11, 1);
+        record.resolve_removed_identity(&cache);
+        assert_eq!(
+            record.event,
+            PolicyChangeEvent::IdentityRemoved {
+                policy,
+                index: 1,
+                identity: Some(identities[1]),
+            }
+        );
+
+        // The cached version may already include the removal.
+        let mut record = removed(10, 1);
+        record.resolve_removed_identity(&cache);
+        assert_eq!(
+            record.event,
+            PolicyChangeEvent::IdentityRemoved {
+                policy,
+                index: 1,
+                identity: None,
+            }
+        );
+
+        let mut record = removed(11, 5);
+        record.resolve_removed_identity(&cache);
+        assert_eq!(
+            record.event,
+            PolicyChangeEvent::IdentityRemoved {
+                policy,
+                index: 5,
+                identity: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_jsonl_sink() {
+        let policy = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let identity = Pubkey::new_unique();
+        let sink = JsonlSink::new(Vec::new());
+
+        let record = PolicyChangeRecord {
+            slot: 42,
+            signature: "sig".to_string(),
+            owner,
+            event: PolicyChangeEvent::IdentityAdded { policy, identity },
+        };
+
+        sink.write(&record).unwrap();
+        sink.write(&record).unwrap();
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let value: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(value["event"], "IdentityAdded");
+        assert_eq!(value["slot"], 42);
+        assert_eq!(value["signature"], "sig");
+        assert_eq!(value["owner"], owner.to_string());
+        assert_eq!(value["policy"], policy.to_string());
+        assert_eq!(value["identity"], identity.to_string());
+    }
+}
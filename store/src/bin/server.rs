@@ -0,0 +1,201 @@
+//! HTTP sidecar exposing a `PolicyStore` to services not written in Rust.
+//!
+//! Endpoints:
+//!
+//! - `POST /check` evaluates an identity against a list of policies.
+//! - `GET /policies/:address` returns the cached policy stored at `address` and its metadata.
+//! - `GET /updates` streams `PolicyDelta`s as server-sent events.
+//! - `GET /metrics` exposes the store and Vixen runtime metrics in the Prometheus text format.
+
+use std::{convert::Infallible, net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use clap::Parser;
+use clap_derive::Parser as DeriveParser;
+use prometheus::{Encoder, TextEncoder};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use solana_pubkey::Pubkey;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use yellowstone_shield_client::{types::PermissionStrategy, PolicyMetadata};
+use yellowstone_shield_store::{
+    metrics::StoreMetrics, CheckError, PolicyStore, PolicyStoreConfig, PolicyStoreTrait,
+};
+
+#[derive(Debug, DeriveParser)]
+#[command(
+    name = "shield-store-server",
+    about = "Serve Yellowstone Shield policy checks over HTTP"
+)]
+struct Opts {
+    /// Path to the policy store TOML config
+    #[arg(short, long)]
+    config: PathBuf,
+
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8787")]
+    listen: SocketAddr,
+}
+
+#[derive(Clone)]
+struct AppState {
+    store: Arc<PolicyStore>,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct CheckRequest {
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    policies: Vec<Pubkey>,
+    #[serde_as(as = "DisplayFromStr")]
+    identity: Pubkey,
+}
+
+#[derive(Serialize)]
+struct CheckResponse {
+    allowed: bool,
+}
+
+#[serde_as]
+#[derive(Serialize)]
+struct PolicyResponse {
+    #[serde_as(as = "DisplayFromStr")]
+    address: Pubkey,
+    strategy: PermissionStrategy,
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    identities: Vec<Pubkey>,
+    /// Whether the policy was locked and can no longer be changed.
+    locked: bool,
+    /// The token metadata of the policy, `null` until it is fetched.
+    metadata: Option<PolicyMetadata>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn error(status: StatusCode, error: impl ToString) -> Response {
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+        }),
+    )
+        .into_response()
+}
+
+async fn check(
+    State(AppState { store, .. }): State<AppState>,
+    Json(request): Json<CheckRequest>,
+) -> Response {
+    match store
+        .snapshot()
+        .is_allowed(&request.policies, &request.identity)
+    {
+        Ok(allowed) => Json(CheckResponse { allowed }).into_response(),
+        Err(e @ CheckError::PolicyNotFound) => error(StatusCode::NOT_FOUND, e),
+    }
+}
+
+async fn policy(
+    State(AppState { store, .. }): State<AppState>,
+    Path(address): Path<String>,
+) -> Response {
+    let Ok(address) = Pubkey::from_str(&address) else {
+        return error(StatusCode::BAD_REQUEST, "Invalid policy address");
+    };
+
+    match store.policy(&address) {
+        Some(policy) => Json(PolicyResponse {
+            address,
+            strategy: policy.strategy,
+            identities: policy
+                .identities
+                .into_iter()
+                .filter(|identity| *identity != Pubkey::default())
+                .collect(),
+            locked: policy.locked,
+            metadata: store.metadata(&address),
+        })
+        .into_response(),
+        None => error(StatusCode::NOT_FOUND, CheckError::PolicyNotFound),
+    }
+}
+
+async fn metrics() -> Response {
+    let mut buffer = Vec::new();
+
+    // The default registry holds both the store and the Vixen runtime metrics.
+    match TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        Ok(()) => buffer.into_response(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+async fn updates(
+    State(AppState { store, .. }): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(store.subscribe()).map(|delta| {
+        let event = match delta {
+            Ok(delta) => Event::default()
+                .event("delta")
+                .json_data(delta)
+                .unwrap_or_else(|e| Event::default().event("error").data(e.to_string())),
+            // The subscriber missed deltas and must refetch the policies it tracks.
+            Err(e) => Event::default().event("lagged").data(e.to_string()),
+        };
+
+        Ok(event)
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .parse_default_env()
+        .init();
+
+    let Opts { config, listen } = Opts::parse();
+    let config = std::fs::read_to_string(config)?;
+    let config: PolicyStoreConfig = toml::from_str(&config)?;
+
+    let local = tokio::task::LocalSet::new();
+
+    let store = PolicyStore::build()
+        .config(config)
+        .metrics(StoreMetrics::register(prometheus::default_registry())?)
+        .run(&local)
+        .await?;
+    let state = AppState {
+        store: Arc::new(store),
+    };
+
+    let app = Router::new()
+        .route("/check", post(check))
+        .route("/policies/:address", get(policy))
+        .route("/updates", get(updates))
+        .route("/metrics", get(metrics))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(listen).await?;
+    log::info!("Listening on {}", listen);
+
+    local
+        .run_until(async move { axum::serve(listener, app).await })
+        .await?;
+
+    Ok(())
+}
@@ -1,11 +1,21 @@
-use std::sync::Arc;
+pub mod audit;
+pub mod metrics;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
 use arc_swap::ArcSwap;
 use hashbrown::{HashMap, HashSet};
 use parking_lot::RwLock;
-use serde::Deserialize;
-use tokio::{sync::mpsc::Sender, task::LocalSet};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use tokio::{
+    sync::{broadcast, mpsc::Sender},
+    task::LocalSet,
+};
 
 use solana_account_decoder_client_types::UiAccountEncoding;
 use solana_client::{
@@ -14,23 +24,115 @@ use solana_client::{
 };
 use solana_commitment_config::CommitmentConfig;
 use solana_pubkey::Pubkey;
-use yellowstone_shield_client::{accounts, types::PermissionStrategy, PolicyTrait};
+use yellowstone_shield_client::{types::PermissionStrategy, DecodedPolicy, PolicyMetadata};
 use yellowstone_shield_parser::accounts_parser::{AccountParser, Policy, ShieldProgramState};
 use yellowstone_vixen::{
     config::{BufferConfig, OptConfig, VixenConfig, YellowstoneConfig},
+    instruction::InstructionPipeline,
+    metrics::{Prometheus, PrometheusConfig},
     Pipeline, Runtime,
 };
 
+use crate::{
+    audit::{AuditHandler, AuditParser, AuditSink},
+    metrics::{ClockParser, SlotHandler, StoreMetrics},
+};
+
+/// Interval at which the cluster tip is polled to compute the update lag metric.
+const TIP_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Maximum number of accounts fetched by a single `getMultipleAccounts` request.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
 pub struct SlotCacheItem<T> {
     slot: u64,
     item: T,
 }
 
+/// Capacity of the broadcast channel carrying policy deltas.
+pub const POLICY_DELTA_CHANNEL_CAPACITY: usize = 1_024;
+
+/// The changes between two consecutive versions of a policy.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyDelta {
+    /// The address of the policy account.
+    #[serde_as(as = "DisplayFromStr")]
+    pub policy: Pubkey,
+    /// The slot of the update that produced the delta.
+    pub slot: u64,
+    /// Identities present in the new version but not in the previous one.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub added: Vec<Pubkey>,
+    /// Identities present in the previous version but not in the new one.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub removed: Vec<Pubkey>,
+    /// Whether the permission strategy differs from the previous version.
+    pub strategy_changed: bool,
+}
+
+impl PolicyDelta {
+    /// Computes the delta between `previous` and `current`.
+    ///
+    /// Empty identity slots (`Pubkey::default()`) are ignored. A policy seen for the first
+    /// time reports all of its identities as added.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the two versions hold the same identities and strategy.
+    pub fn new(
+        policy: Pubkey,
+        slot: u64,
+        previous: Option<&Policy>,
+        current: &Policy,
+    ) -> Option<Self> {
+        let empty = Pubkey::default();
+        let before: HashSet<&Pubkey> = previous
+            .map(|p| p.identities.iter().filter(|i| **i != empty).collect())
+            .unwrap_or_default();
+        let after: HashSet<&Pubkey> = current.identities.iter().filter(|i| **i != empty).collect();
+
+        let mut seen = HashSet::new();
+        let added: Vec<Pubkey> = current
+            .identities
+            .iter()
+            .filter(|i| **i != empty && !before.contains(i) && seen.insert(**i))
+            .copied()
+            .collect();
+        let removed: Vec<Pubkey> = previous
+            .map(|p| {
+                p.identities
+                    .iter()
+                    .filter(|i| **i != empty && !after.contains(i) && seen.insert(**i))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let strategy_changed = previous.is_some_and(|p| p.strategy != current.strategy);
+
+        if added.is_empty() && removed.is_empty() && !strategy_changed {
+            return None;
+        }
+
+        Some(Self {
+            policy,
+            slot,
+            added,
+            removed,
+            strategy_changed,
+        })
+    }
+}
+
 /// A thread-safe cache for storing policies by their associated public keys.
 pub struct PolicyCache {
     /// A read-write lock-protected hash map that stores policies keyed by public keys.
     /// Each entry contains a tuple of the slot number and the policy.
     policies: RwLock<HashMap<Pubkey, SlotCacheItem<Policy>>>,
+    /// Broadcasts the delta of every accepted policy update.
+    deltas: broadcast::Sender<PolicyDelta>,
+    /// The token metadata of the policies, keyed by policy address.
+    metadata: RwLock<HashMap<Pubkey, PolicyMetadata>>,
 }
 
 impl Default for PolicyCache {
@@ -46,14 +148,21 @@ impl PolicyCache {
     ///
     /// A new instance of `PolicyCache` with an empty internal storage.
     pub fn new() -> Self {
+        let (deltas, _) = broadcast::channel(POLICY_DELTA_CHANNEL_CAPACITY);
+
         Self {
             policies: RwLock::new(HashMap::new()),
+            deltas,
+            metadata: RwLock::new(HashMap::new()),
         }
     }
 
     /// Inserts a policy into the cache, associating it with the given public key.
     /// Only updates if the incoming slot is greater than the current slot.
     ///
+    /// When the update is accepted and changes the policy, a `PolicyDelta` against the
+    /// previous version is broadcast to subscribers.
+    ///
     /// # Arguments
     ///
     /// * `pubkey` - The public key to associate with the policy.
@@ -61,15 +170,28 @@ impl PolicyCache {
     /// * `policy` - The policy to be stored in the cache.
     pub fn insert(&self, pubkey: Pubkey, slot: u64, item: Policy) {
         let mut policies = self.policies.write();
-        if let Some(current_item) = policies.get(&pubkey) {
-            if slot > current_item.slot {
-                policies.insert(pubkey, SlotCacheItem { slot, item });
-            }
-        } else {
-            policies.insert(pubkey, SlotCacheItem { slot, item });
+        let previous = match policies.get(&pubkey) {
+            Some(current_item) if slot <= current_item.slot => return,
+            Some(current_item) => Some(&current_item.item),
+            None => None,
+        };
+
+        let delta = PolicyDelta::new(pubkey, slot, previous, &item);
+
+        policies.insert(pubkey, SlotCacheItem { slot, item });
+        drop(policies);
+
+        if let Some(delta) = delta {
+            // Sending only fails when there are no subscribers.
+            let _ = self.deltas.send(delta);
         }
     }
 
+    /// Subscribes to the deltas of policy updates accepted by the cache.
+    pub fn subscribe(&self) -> broadcast::Receiver<PolicyDelta> {
+        self.deltas.subscribe()
+    }
+
     /// Retrieves a policy from the cache associated with the given public key.
     ///
     /// # Arguments
@@ -87,6 +209,16 @@ impl PolicyCache {
             .map(|item| item.item.clone())
     }
 
+    /// Retrieves the cached policy only if it was updated before `slot`, i.e. the version
+    /// an instruction executed at `slot` applied to.
+    pub fn get_before(&self, pubkey: &Pubkey, slot: u64) -> Option<Policy> {
+        self.policies
+            .read()
+            .get(pubkey)
+            .filter(|item| item.slot < slot)
+            .map(|item| item.item.clone())
+    }
+
     /// Removes a policy from the cache associated with the given public key.
     ///
     /// # Arguments
@@ -97,9 +229,24 @@ impl PolicyCache {
     ///
     /// `Some(())` if a policy was removed, or `None` if no policy was associated with the given public key.
     pub fn remove(&self, pubkey: &Pubkey) -> Option<()> {
+        self.metadata.write().remove(pubkey);
         self.policies.write().remove(pubkey).map(|_| ())
     }
 
+    /// Records the token metadata of the policy associated with the given public key.
+    pub fn insert_metadata(&self, pubkey: Pubkey, metadata: PolicyMetadata) {
+        self.metadata.write().insert(pubkey, metadata);
+    }
+
+    /// Retrieves the token metadata of the policy associated with the given public key.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the metadata was not fetched yet or the policy is not bound to a mint.
+    pub fn metadata(&self, pubkey: &Pubkey) -> Option<PolicyMetadata> {
+        self.metadata.read().get(pubkey).cloned()
+    }
+
     /// Retrieves all policies currently stored in the cache.
     ///
     /// # Returns
@@ -132,6 +279,8 @@ pub struct Snapshot {
     /// A hash set that stores tuples of policy and identity public keys for quick lookup.
     lookup: HashSet<(Pubkey, Pubkey)>,
     strategies: HashMap<Pubkey, PermissionStrategy>,
+    /// Metrics recording the outcome of each check, if enabled.
+    metrics: Option<Arc<StoreMetrics>>,
 }
 
 impl Snapshot {
@@ -156,7 +305,34 @@ impl Snapshot {
             }
         }
 
-        Self { lookup, strategies }
+        Self {
+            lookup,
+            strategies,
+            metrics: None,
+        }
+    }
+
+    /// Records the outcome of every check made against this snapshot in `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<StoreMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Rebuilds a snapshot from the cache, recording its latency and size when metrics are enabled.
+    fn rebuild(cache: &PolicyCache, metrics: Option<&Arc<StoreMetrics>>) -> Self {
+        let Some(metrics) = metrics else {
+            return Self::new(cache);
+        };
+
+        let started = Instant::now();
+        let snapshot = Self::new(cache).with_metrics(Arc::clone(metrics));
+        metrics.observe_rebuild(
+            started.elapsed(),
+            snapshot.strategies.len(),
+            snapshot.lookup.len(),
+        );
+
+        snapshot
     }
 
     /// Determines if a identity is allowed by any of the specified policy pubkey.
@@ -193,6 +369,17 @@ impl Snapshot {
     ///
     /// `true` if the identity is allowed by any of the specified policies, `false` otherwise.
     pub fn is_allowed(&self, policies: &[Pubkey], identity: &Pubkey) -> Result<bool, CheckError> {
+        let result = self.evaluate(policies, identity);
+
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_check(&result);
+        }
+
+        result
+    }
+
+    /// Evaluates the policies for the identity as described in `is_allowed`.
+    fn evaluate(&self, policies: &[Pubkey], identity: &Pubkey) -> Result<bool, CheckError> {
         let mut not_found = true;
 
         for address in policies.iter() {
@@ -251,37 +438,53 @@ impl PolicyRpcClient {
             .await?
             .into_iter()
             .filter_map(|(address, account)| {
-                let data: &[u8] = &account.data;
-
-                let (strategy, identities) = match data[0] {
-                    0 => {
-                        let strategy = accounts::Policy::from_bytes(data)
-                            .ok()?
-                            .try_strategy()
-                            .ok()?;
-                        let identities = accounts::Policy::try_deserialize_identities(data).ok()?;
-                        Some((strategy, identities))
-                    }
-                    1 => {
-                        let strategy = accounts::PolicyV2::from_bytes(data)
-                            .ok()?
-                            .try_strategy()
-                            .ok()?;
-                        let identities =
-                            accounts::PolicyV2::try_deserialize_identities(data).ok()?;
-                        Some((strategy, identities))
-                    }
-                    _ => None,
-                }?;
-
-                let policy = Policy::new(strategy, identities);
-
-                Some((address, policy))
+                let DecodedPolicy {
+                    strategy,
+                    identities,
+                    locked,
+                    ..
+                } = DecodedPolicy::try_from_bytes(&account.data).ok()?;
+
+                Some((address, Policy::new(strategy, identities).locked(locked)))
             })
             .collect::<Vec<_>>();
 
         Ok(SlotRpcResponse { slot, result })
     }
+
+    /// Fetches the token metadata of the given policies.
+    ///
+    /// Policies without a mint recorded in their header, missing, or bound to a mint without
+    /// metadata are skipped.
+    pub async fn metadata(&self, policies: &[Pubkey]) -> Result<Vec<(Pubkey, PolicyMetadata)>> {
+        let mut mints = Vec::new();
+        for chunk in policies.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.0.get_multiple_accounts(chunk).await?;
+            mints.extend(chunk.iter().zip(accounts).filter_map(|(address, account)| {
+                let mint = DecodedPolicy::try_from_bytes(&account?.data).ok()?.mint?;
+                Some((*address, mint))
+            }));
+        }
+
+        let mut metadata = Vec::with_capacity(mints.len());
+        for chunk in mints.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let keys: Vec<Pubkey> = chunk.iter().map(|(_, mint)| *mint).collect();
+            let accounts = self.0.get_multiple_accounts(&keys).await?;
+            metadata.extend(
+                chunk
+                    .iter()
+                    .zip(accounts)
+                    .filter_map(|((address, _), account)| {
+                        Some((
+                            *address,
+                            PolicyMetadata::try_from_mint(&account?.data).ok()?,
+                        ))
+                    }),
+            );
+        }
+
+        Ok(metadata)
+    }
 }
 
 impl From<PoliciesSlotRpcResponse> for PolicyCache {
@@ -327,6 +530,8 @@ pub trait PolicyStoreTrait {
 pub struct PolicyStore {
     /// An atomic reference-counted snapshot of the current state of policies.
     snapshot: Arc<ArcSwap<Snapshot>>,
+    /// The cache the snapshot is rebuilt from.
+    cache: Arc<PolicyCache>,
 }
 
 impl PolicyStore {
@@ -334,13 +539,35 @@ impl PolicyStore {
     ///
     /// # Arguments
     ///
-    /// * `policies` - A response containing policies and their associated slots.
+    /// * `snapshot` - The snapshot kept up to date by the subscription.
+    /// * `cache` - The cache holding the policies the snapshot is built from.
     ///
     /// # Returns
     ///
     /// A new instance of `PolicyStore`.
-    pub fn new(snapshot: Arc<ArcSwap<Snapshot>>) -> Self {
-        Self { snapshot }
+    pub fn new(snapshot: Arc<ArcSwap<Snapshot>>, cache: Arc<PolicyCache>) -> Self {
+        Self { snapshot, cache }
+    }
+
+    /// Retrieves the current version of the policy stored at the given address.
+    pub fn policy(&self, address: &Pubkey) -> Option<Policy> {
+        self.cache.get(address)
+    }
+
+    /// Retrieves the token metadata of the policy stored at the given address.
+    ///
+    /// The metadata is fetched when the store starts and when a policy is first seen, changes
+    /// made to the metadata afterwards are picked up on restart.
+    pub fn metadata(&self, address: &Pubkey) -> Option<PolicyMetadata> {
+        self.cache.metadata(address)
+    }
+
+    /// Subscribes to the changes of policies as they are applied to the store.
+    ///
+    /// Receivers falling more than `POLICY_DELTA_CHANNEL_CAPACITY` deltas behind observe
+    /// `RecvError::Lagged` and should resynchronize from a snapshot.
+    pub fn subscribe(&self) -> broadcast::Receiver<PolicyDelta> {
+        self.cache.subscribe()
     }
 }
 
@@ -387,6 +614,9 @@ pub struct PolicyStoreRpcConfig {
 pub struct PolicyStoreConfig {
     pub rpc: PolicyStoreRpcConfig,
     pub grpc: YellowstoneConfig,
+    /// Vixen runtime metrics, recorded in `prometheus::default_registry()`.
+    #[serde(default)]
+    pub metrics: OptConfig<PrometheusConfig>,
 }
 
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
@@ -395,11 +625,15 @@ pub enum BuilderError {
     NoConfig,
     #[error("Unable to deserialize policy")]
     DeserializePolicy,
+    #[error("Unable to create instruction pipeline")]
+    InstructionPipeline,
 }
 
 #[derive(Default)]
 pub struct PolicyStoreBuilder {
     config: Option<PolicyStoreConfig>,
+    audit: Option<Box<dyn AuditSink>>,
+    metrics: Option<Arc<StoreMetrics>>,
 }
 
 impl PolicyStoreBuilder {
@@ -409,32 +643,86 @@ impl PolicyStoreBuilder {
         self
     }
 
+    /// Record a policy change event in the given sink for every Shield instruction observed.
+    pub fn audit(&mut self, sink: impl AuditSink + 'static) -> &mut Self {
+        self.audit = Some(Box::new(sink));
+
+        self
+    }
+
+    /// Collect store metrics, see `StoreMetrics::register`. The update lag is measured
+    /// against the Clock sysvar updates delivered by the subscription.
+    pub fn metrics(&mut self, metrics: StoreMetrics) -> &mut Self {
+        self.metrics = Some(Arc::new(metrics));
+
+        self
+    }
+
     pub async fn run(&mut self, local: &LocalSet) -> Result<PolicyStore> {
         let config = self.config.take().ok_or(BuilderError::NoConfig)?;
-        let rpc = RpcClient::new(config.rpc.endpoint);
-
-        let policies = PolicyRpcClient::new(rpc)
-            .list(&yellowstone_shield_client::ID)
-            .await?;
+        let metrics = self.metrics.take();
+        let rpc = Arc::new(PolicyRpcClient::new(RpcClient::new(
+            config.rpc.endpoint.clone(),
+        )));
+
+        let policies = rpc.list(&yellowstone_shield_client::ID).await?;
+        let addresses: Vec<Pubkey> = policies
+            .result
+            .iter()
+            .map(|(address, _)| *address)
+            .collect();
 
-        let cache = Arc::new(policies.into());
-        let snapshot = Arc::new(ArcSwap::from_pointee(Snapshot::new(&cache)));
+        let cache: Arc<PolicyCache> = Arc::new(policies.into());
+        for (address, metadata) in rpc.metadata(&addresses).await? {
+            cache.insert_metadata(address, metadata);
+        }
+        let snapshot = Arc::new(ArcSwap::from_pointee(Snapshot::rebuild(
+            &cache,
+            metrics.as_ref(),
+        )));
 
         let (sender, mut receiver) = tokio::sync::mpsc::channel::<ShieldProgramState>(10_000);
 
         let vixen = VixenConfig {
             yellowstone: config.grpc,
             buffer: BufferConfig::default(),
-            metrics: OptConfig::default(),
+            metrics: config.metrics,
         };
 
         let pipeline = Pipeline::new(AccountParser, [PolicyHandler::new(sender)]);
-        let runtime = Runtime::builder()
-            .account(pipeline)
+        let mut runtime = Runtime::builder().account(pipeline).metrics(Prometheus);
+
+        if let Some(metrics) = metrics.as_ref().map(Arc::clone) {
+            runtime = runtime.account(Pipeline::new(ClockParser, [SlotHandler::new(metrics)]));
+        }
+
+        if let Some(sink) = self.audit.take() {
+            let handler = AuditHandler::spawn(sink, Arc::clone(&cache));
+            let audit = Pipeline::new(AuditParser, [handler]);
+            runtime = runtime.instruction(
+                InstructionPipeline::new(audit).ok_or(BuilderError::InstructionPipeline)?,
+            );
+        }
+
+        let runtime = runtime
             .commitment_level(yellowstone_vixen::CommitmentLevel::Confirmed)
             .build(vixen);
 
-        let cache = Arc::clone(&cache);
+        let subscription_cache = Arc::clone(&cache);
+
+        if let Some(metrics) = metrics.as_ref().map(Arc::clone) {
+            let rpc = RpcClient::new(config.rpc.endpoint);
+            local.spawn_local(async move {
+                let mut interval = tokio::time::interval(TIP_POLL_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    match rpc.get_slot().await {
+                        Ok(tip) => metrics.observe_tip(tip),
+                        Err(e) => log::warn!("Failed to fetch cluster slot: {:?}", e),
+                    }
+                }
+            });
+        }
 
         let subscription_snapshot = Arc::clone(&snapshot);
         local.spawn_local(Box::pin(async move {
@@ -446,12 +734,34 @@ impl PolicyStoreBuilder {
 
             while let Some(value) = receiver.recv().await {
                 let ShieldProgramState::Policy(slot, pubkey, policy) = value;
-                cache.insert(pubkey, slot, policy);
-                subscription_snapshot.store(Arc::new(Snapshot::new(&cache)));
+
+                // Policies created after the store started have no metadata yet.
+                if subscription_cache.get(&pubkey).is_none() {
+                    let (rpc, cache) = (Arc::clone(&rpc), Arc::clone(&subscription_cache));
+                    tokio::task::spawn_local(async move {
+                        match rpc.metadata(&[pubkey]).await {
+                            Ok(metadata) => {
+                                for (address, metadata) in metadata {
+                                    cache.insert_metadata(address, metadata);
+                                }
+                            }
+                            Err(e) => log::warn!("Failed to fetch policy metadata: {:?}", e),
+                        }
+                    });
+                }
+
+                subscription_cache.insert(pubkey, slot, policy);
+                if let Some(metrics) = &metrics {
+                    metrics.observe_update(slot);
+                }
+                subscription_snapshot.store(Arc::new(Snapshot::rebuild(
+                    &subscription_cache,
+                    metrics.as_ref(),
+                )));
             }
         }) as SubscriptionTask);
 
-        Ok(PolicyStore::new(snapshot))
+        Ok(PolicyStore::new(snapshot, cache))
     }
 }
 
@@ -484,6 +794,39 @@ mod tests {
         assert_eq!(retrieved_policy.identities, policy.identities);
     }
 
+    #[test]
+    fn test_policy_cache_metadata() {
+        let cache = PolicyCache::new();
+        let address = Pubkey::new_unique();
+        let metadata = PolicyMetadata::new(
+            "Sandwichers".to_string(),
+            "SAND".to_string(),
+            "https://example.com/sandwichers.json".to_string(),
+            &[
+                ("category".to_string(), "sandwich".to_string()),
+                ("unknown".to_string(), "ignored".to_string()),
+            ],
+        );
+
+        cache.insert(
+            address,
+            1,
+            Policy::new(
+                yellowstone_shield_client::types::PermissionStrategy::Deny,
+                vec![Pubkey::new_unique()],
+            ),
+        );
+        cache.insert_metadata(address, metadata.clone());
+
+        let retrieved = cache.metadata(&address).unwrap();
+        assert_eq!(retrieved, metadata);
+        assert_eq!(retrieved.category.as_deref(), Some("sandwich"));
+        assert_eq!(retrieved.description, None);
+
+        cache.remove(&address);
+        assert!(cache.metadata(&address).is_none());
+    }
+
     #[test]
     fn test_policy_cache_all() {
         let cache = PolicyCache::new();
@@ -530,6 +873,108 @@ mod tests {
         assert!(cache.get(&address).is_none());
     }
 
+    #[test]
+    fn test_policy_cache_broadcasts_delta() {
+        let cache = PolicyCache::new();
+        let mut deltas = cache.subscribe();
+        let address = Pubkey::new_unique();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let third = Pubkey::new_unique();
+
+        cache.insert(
+            address,
+            1,
+            Policy::new(
+                yellowstone_shield_client::types::PermissionStrategy::Deny,
+                vec![first, second],
+            ),
+        );
+
+        assert_eq!(
+            deltas.try_recv().unwrap(),
+            PolicyDelta {
+                policy: address,
+                slot: 1,
+                added: vec![first, second],
+                removed: vec![],
+                strategy_changed: false,
+            }
+        );
+
+        cache.insert(
+            address,
+            2,
+            Policy::new(
+                yellowstone_shield_client::types::PermissionStrategy::Allow,
+                vec![Pubkey::default(), second, third],
+            ),
+        );
+
+        assert_eq!(
+            deltas.try_recv().unwrap(),
+            PolicyDelta {
+                policy: address,
+                slot: 2,
+                added: vec![third],
+                removed: vec![first],
+                strategy_changed: true,
+            }
+        );
+
+        // Stale and unchanged updates do not produce a delta.
+        cache.insert(
+            address,
+            1,
+            Policy::new(
+                yellowstone_shield_client::types::PermissionStrategy::Deny,
+                vec![],
+            ),
+        );
+        cache.insert(
+            address,
+            3,
+            Policy::new(
+                yellowstone_shield_client::types::PermissionStrategy::Allow,
+                vec![second, third],
+            ),
+        );
+
+        assert!(deltas.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_policy_cache_locked() {
+        let cache = PolicyCache::new();
+        let mut deltas = cache.subscribe();
+        let address = Pubkey::new_unique();
+        let identities = vec![Pubkey::new_unique()];
+
+        cache.insert(
+            address,
+            1,
+            Policy::new(
+                yellowstone_shield_client::types::PermissionStrategy::Deny,
+                identities.clone(),
+            ),
+        );
+        assert!(!cache.get(&address).unwrap().locked);
+        deltas.try_recv().unwrap();
+
+        // Locking does not change the identities, the new version is kept without a delta.
+        cache.insert(
+            address,
+            2,
+            Policy::new(
+                yellowstone_shield_client::types::PermissionStrategy::Deny,
+                identities,
+            )
+            .locked(true),
+        );
+        assert!(cache.get(&address).unwrap().locked);
+        assert!(deltas.try_recv().is_err());
+    }
+
     #[test]
     fn test_snapshot_is_allowed() {
         let cache = PolicyCache::new();
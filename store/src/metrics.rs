@@ -0,0 +1,219 @@
+use std::{sync::Arc, time::Duration};
+
+use prometheus::{Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry};
+use solana_pubkey::{pubkey, Pubkey};
+use yellowstone_vixen_core::{AccountUpdate, ParseResult, Parser, Prefilter};
+
+use crate::CheckError;
+
+/// The Clock sysvar, updated every slot.
+const CLOCK_SYSVAR: Pubkey = pubkey!("SysvarC1ock11111111111111111111111111111111");
+
+/// Prometheus metrics describing the activity of a `PolicyStore`.
+///
+/// Metrics are opt-in: they are only collected once registered with
+/// `PolicyStoreBuilder::metrics`. Register them on `prometheus::default_registry()`, the
+/// registry the Vixen runtime metrics are recorded in, to expose both from one endpoint.
+pub struct StoreMetrics {
+    /// Number of policy account updates applied to the cache.
+    updates: IntCounter,
+    /// Slot of the most recent policy account update.
+    last_update_slot: IntGauge,
+    /// Slot of the most recent update delivered by the Vixen runtime, policy or not.
+    stream_slot: IntGauge,
+    /// Distance in slots between the cluster tip and `stream_slot`.
+    lag_slots: IntGauge,
+    /// Time spent rebuilding the snapshot after an update.
+    rebuild_seconds: Histogram,
+    /// Number of policies in the current snapshot.
+    policies: IntGauge,
+    /// Number of policy/identity pairs in the current snapshot.
+    identities: IntGauge,
+    checks_allowed: IntCounter,
+    checks_denied: IntCounter,
+    checks_policy_not_found: IntCounter,
+}
+
+impl StoreMetrics {
+    /// Creates the store metrics and registers them with the given registry.
+    pub fn register(registry: &Registry) -> prometheus::Result<Self> {
+        let updates = IntCounter::new(
+            "shield_store_updates_total",
+            "Number of policy account updates applied to the cache",
+        )?;
+        let last_update_slot = IntGauge::new(
+            "shield_store_last_update_slot",
+            "Slot of the most recent policy account update",
+        )?;
+        let stream_slot = IntGauge::new(
+            "shield_store_stream_slot",
+            "Slot of the most recent update delivered by the subscription",
+        )?;
+        let lag_slots = IntGauge::new(
+            "shield_store_update_lag_slots",
+            "Slots between the cluster tip and the most recent update delivered by the subscription",
+        )?;
+        let rebuild_seconds = Histogram::with_opts(
+            HistogramOpts::new(
+                "shield_store_snapshot_rebuild_seconds",
+                "Time spent rebuilding the policy snapshot",
+            )
+            .buckets(prometheus::exponential_buckets(0.0001, 2.0, 16)?),
+        )?;
+        let policies = IntGauge::new(
+            "shield_store_policies",
+            "Number of policies in the current snapshot",
+        )?;
+        let identities = IntGauge::new(
+            "shield_store_identities",
+            "Number of identities across all policies in the current snapshot",
+        )?;
+        let checks = IntCounterVec::new(
+            Opts::new(
+                "shield_store_checks_total",
+                "Number of permission checks by verdict",
+            ),
+            &["verdict"],
+        )?;
+
+        registry.register(Box::new(updates.clone()))?;
+        registry.register(Box::new(last_update_slot.clone()))?;
+        registry.register(Box::new(stream_slot.clone()))?;
+        registry.register(Box::new(lag_slots.clone()))?;
+        registry.register(Box::new(rebuild_seconds.clone()))?;
+        registry.register(Box::new(policies.clone()))?;
+        registry.register(Box::new(identities.clone()))?;
+        registry.register(Box::new(checks.clone()))?;
+
+        Ok(Self {
+            updates,
+            last_update_slot,
+            stream_slot,
+            lag_slots,
+            rebuild_seconds,
+            policies,
+            identities,
+            checks_allowed: checks.with_label_values(&["allowed"]),
+            checks_denied: checks.with_label_values(&["denied"]),
+            checks_policy_not_found: checks.with_label_values(&["policy_not_found"]),
+        })
+    }
+
+    pub(crate) fn observe_update(&self, slot: u64) {
+        self.updates.inc();
+        self.last_update_slot.set(slot as i64);
+        self.observe_stream_slot(slot);
+    }
+
+    /// Records the slot of an update delivered by the subscription. Updates are not
+    /// delivered in slot order, so the gauge only moves forward.
+    pub(crate) fn observe_stream_slot(&self, slot: u64) {
+        if slot as i64 > self.stream_slot.get() {
+            self.stream_slot.set(slot as i64);
+        }
+    }
+
+    pub(crate) fn observe_tip(&self, tip: u64) {
+        let last = self.stream_slot.get() as u64;
+        self.lag_slots.set(tip.saturating_sub(last) as i64);
+    }
+
+    pub(crate) fn observe_rebuild(&self, elapsed: Duration, policies: usize, identities: usize) {
+        self.rebuild_seconds.observe(elapsed.as_secs_f64());
+        self.policies.set(policies as i64);
+        self.identities.set(identities as i64);
+    }
+
+    pub(crate) fn observe_check(&self, result: &Result<bool, CheckError>) {
+        match result {
+            Ok(true) => self.checks_allowed.inc(),
+            Ok(false) => self.checks_denied.inc(),
+            Err(CheckError::PolicyNotFound) => self.checks_policy_not_found.inc(),
+        }
+    }
+}
+
+/// Parses the Clock sysvar account updates into their slot, so the update lag is measured
+/// even when no policy changes.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ClockParser;
+
+impl Parser for ClockParser {
+    type Input = AccountUpdate;
+    type Output = u64;
+
+    fn id(&self) -> std::borrow::Cow<str> {
+        "shield::ClockParser".into()
+    }
+
+    fn prefilter(&self) -> Prefilter {
+        Prefilter::builder()
+            .accounts([CLOCK_SYSVAR])
+            .build()
+            .unwrap()
+    }
+
+    async fn parse(&self, update: &AccountUpdate) -> ParseResult<Self::Output> {
+        Ok(update.slot)
+    }
+}
+
+/// Vixen handler recording the slot of every Clock sysvar update.
+pub(crate) struct SlotHandler {
+    metrics: Arc<StoreMetrics>,
+}
+
+impl SlotHandler {
+    pub(crate) fn new(metrics: Arc<StoreMetrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl std::fmt::Debug for SlotHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlotHandler").finish_non_exhaustive()
+    }
+}
+
+impl yellowstone_vixen::Handler<u64> for SlotHandler {
+    async fn handle(&self, slot: &u64) -> yellowstone_vixen::HandlerResult<()> {
+        self.metrics.observe_stream_slot(*slot);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_metrics() {
+        let registry = Registry::new();
+        let metrics = StoreMetrics::register(&registry).unwrap();
+
+        metrics.observe_update(100);
+        metrics.observe_tip(112);
+        assert_eq!(metrics.lag_slots.get(), 12);
+
+        // Slots delivered without a policy update still reduce the lag.
+        metrics.observe_stream_slot(110);
+        metrics.observe_stream_slot(105);
+        metrics.observe_tip(112);
+        metrics.observe_check(&Ok(true));
+        metrics.observe_check(&Ok(false));
+        metrics.observe_check(&Err(CheckError::PolicyNotFound));
+        metrics.observe_check(&Err(CheckError::PolicyNotFound));
+
+        assert_eq!(metrics.updates.get(), 1);
+        assert_eq!(metrics.last_update_slot.get(), 100);
+        assert_eq!(metrics.stream_slot.get(), 110);
+        assert_eq!(metrics.lag_slots.get(), 2);
+        assert_eq!(metrics.checks_allowed.get(), 1);
+        assert_eq!(metrics.checks_denied.get(), 1);
+        assert_eq!(metrics.checks_policy_not_found.get(), 2);
+
+        // Registering twice in the same registry is rejected.
+        assert!(StoreMetrics::register(&registry).is_err());
+    }
+}